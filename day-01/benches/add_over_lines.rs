@@ -0,0 +1,75 @@
+mod common;
+
+use common::{bench, xorshift};
+use day_01::{add_over_lines, concat_first_and_last_digits, CalibrationError, DigitsOnly};
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+const LINES: usize = 1_000_000;
+
+fn add_over_lines_locked(
+    lines: &[&str],
+    worker_count: usize,
+    adder: impl Fn(&str) -> Result<u32, CalibrationError> + Send + Copy,
+) -> u32 {
+    thread::scope(|s| {
+        let chunks = lines.chunks(lines.len() / worker_count + 1);
+        let sum = Arc::new(Mutex::new(0u32));
+        let mut handles = vec![];
+
+        for chunk in chunks {
+            let sum = Arc::clone(&sum);
+            handles.push(s.spawn(move || {
+                for line in chunk {
+                    let mut sum = sum.lock().unwrap();
+                    *sum += adder(line).unwrap();
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let result = *sum.lock().unwrap();
+        result
+    })
+}
+
+fn generate_lines(count: usize) -> Vec<String> {
+    let mut next = xorshift();
+
+    (0..count)
+        .map(|_| {
+            (0..8 + next() % 32)
+                .map(|_| match next() % 4 {
+                    0 => char::from(b'0' + (next() % 10) as u8),
+                    _ => char::from(b'a' + (next() % 26) as u8),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn main() {
+    let lines = generate_lines(LINES);
+    let lines: Vec<&str> = lines.iter().map(|l| l.as_str()).collect();
+    let workers_count: usize = thread::available_parallelism()
+        .unwrap_or(NonZeroUsize::new(2).unwrap())
+        .into();
+
+    println!("{} lines, {} workers", LINES, workers_count);
+
+    let locked = bench("locked", || {
+        add_over_lines_locked(&lines, workers_count, concat_first_and_last_digits)
+    });
+
+    let local = bench("local", || {
+        add_over_lines(&lines, workers_count, &DigitsOnly::default()).unwrap()
+    });
+
+    assert_eq!(locked, local);
+}
@@ -0,0 +1,32 @@
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+pub fn xorshift() -> impl FnMut() -> u64 {
+    let mut state = 0x2545f4914f6cdd1du64;
+
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    }
+}
+
+pub fn bench<T>(name: &str, run: impl Fn() -> T) -> T {
+    let mut timings = vec![];
+    let mut result = None;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        result = Some(run());
+        timings.push(start.elapsed());
+    }
+
+    let best = timings.iter().min().unwrap();
+    let mean = timings.iter().sum::<Duration>() / RUNS as u32;
+
+    println!("{:<12} best {:>10.2?}  mean {:>10.2?}", name, best, mean);
+
+    result.unwrap()
+}
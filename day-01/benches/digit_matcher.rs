@@ -0,0 +1,69 @@
+mod common;
+
+use common::{bench, xorshift};
+use day_01::{decimal_digit, DigitMatcher, DigitVocabulary};
+
+const LINES: usize = 2_000;
+const LINE_LENGTH: usize = 2_000;
+
+fn get_first_converted_digit(input: &str, vocabulary: &DigitVocabulary) -> Option<u32> {
+    input
+        .char_indices()
+        .find_map(|(i, c)| decimal_digit(c).or_else(|| vocabulary.word_at(&input[i..])))
+}
+
+fn get_last_converted_digit(input: &str, vocabulary: &DigitVocabulary) -> Option<u32> {
+    input.char_indices().rev().find_map(|(i, c)| {
+        decimal_digit(c).or_else(|| vocabulary.word_ending(&input[..i + c.len_utf8()]))
+    })
+}
+
+fn generate_lines(count: usize, length: usize) -> Vec<String> {
+    let mut next = xorshift();
+
+    let alphabet: Vec<char> = "abcdfghjklmnopqrstuvwxyz".chars().collect();
+
+    (0..count)
+        .map(|_| {
+            let mut line: String = (0..length)
+                .map(|_| alphabet[next() as usize % alphabet.len()])
+                .collect();
+
+            let middle = length / 2;
+            line.replace_range(middle..middle + 5, "eight");
+            line
+        })
+        .collect()
+}
+
+fn main() {
+    let lines = generate_lines(LINES, LINE_LENGTH);
+    let vocabulary = DigitVocabulary::english();
+    let matcher = DigitMatcher::new(&vocabulary);
+
+    println!("{} lines of {} characters", LINES, LINE_LENGTH);
+
+    let naive = bench("naive", || {
+        lines
+            .iter()
+            .map(|line| {
+                let first = get_first_converted_digit(line, &vocabulary).unwrap_or(0);
+                let last = get_last_converted_digit(line, &vocabulary).unwrap_or(0);
+                (first * 10 + last) as u64
+            })
+            .sum::<u64>()
+    });
+
+    let automaton = bench("automaton", || {
+        lines
+            .iter()
+            .map(|line| {
+                let first = matcher.first(line).map_or(0, |t| t.value);
+                let last = matcher.last(line).map_or(0, |t| t.value);
+                (first * 10 + last) as u64
+            })
+            .sum::<u64>()
+    });
+
+    assert_eq!(naive, automaton);
+}
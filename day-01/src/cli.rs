@@ -0,0 +1,150 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+    fs::{self, File},
+    io::BufReader,
+    num::NonZeroUsize,
+    process::ExitCode,
+    thread,
+};
+
+use crate::{add_over_reader, CalibrationReport, Explain, NoDigitPolicy};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplainFormat {
+    Table,
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    pub path: Option<String>,
+    pub explain: Option<ExplainFormat>,
+    pub no_digit: NoDigitPolicy,
+    pub compound: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError(String);
+
+impl Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\nusage: [--explain[=table|jsonl]] [--no-digit=zero|skip|fail] [--compound] [input file]",
+            self.0
+        )
+    }
+}
+
+impl Error for UsageError {}
+
+impl Options {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, UsageError> {
+        let mut options = Self::default();
+
+        for arg in args {
+            match arg.split_once('=') {
+                _ if arg == "--explain" => options.explain = Some(ExplainFormat::Table),
+                _ if arg == "--compound" => options.compound = true,
+                Some(("--explain", "table")) => options.explain = Some(ExplainFormat::Table),
+                Some(("--explain", "jsonl")) => options.explain = Some(ExplainFormat::Jsonl),
+                Some(("--no-digit", "zero")) => options.no_digit = NoDigitPolicy::Zero,
+                Some(("--no-digit", "skip")) => options.no_digit = NoDigitPolicy::Skip,
+                Some(("--no-digit", "fail")) => options.no_digit = NoDigitPolicy::Fail,
+                _ if arg.starts_with("--") => {
+                    return Err(UsageError(format!("unknown option `{}`", arg)))
+                }
+                _ if options.path.is_some() => {
+                    return Err(UsageError(format!("unexpected argument `{}`", arg)))
+                }
+                _ => options.path = Some(arg),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn run<C>(calibrator: &C, options: &Options, default_input: &str) -> Result<(), Box<dyn Error>>
+where
+    C: Explain + Sync,
+    C::Error: Debug + Display + Send + 'static,
+{
+    if let Some(format) = options.explain {
+        let input = match &options.path {
+            Some(path) => fs::read_to_string(path)?,
+            None => default_input.to_string(),
+        };
+
+        let report = CalibrationReport::new(input.lines(), calibrator);
+
+        match format {
+            ExplainFormat::Table => println!("{}", report.to_table()),
+            ExplainFormat::Jsonl => print!("{}", report.to_jsonl()),
+        }
+
+        return Ok(());
+    }
+
+    let workers_count: usize = thread::available_parallelism()
+        .unwrap_or(NonZeroUsize::new(2).unwrap())
+        .into();
+
+    let sum: u64 = match &options.path {
+        Some(path) => {
+            add_over_reader(BufReader::new(File::open(path)?), workers_count, calibrator)?
+        }
+        None => add_over_reader(default_input.as_bytes(), workers_count, calibrator)?,
+    };
+
+    println!("Result: {}", sum);
+
+    Ok(())
+}
+
+pub fn report(result: Result<(), Box<dyn Error>>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, UsageError> {
+        Options::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(&[]), Ok(Options::default()));
+        assert_eq!(
+            parse(&["--explain", "input.txt"]),
+            Ok(Options {
+                path: Some("input.txt".to_string()),
+                explain: Some(ExplainFormat::Table),
+                no_digit: NoDigitPolicy::Zero,
+                compound: false,
+            })
+        );
+        assert_eq!(
+            parse(&["--explain=jsonl"]).unwrap().explain,
+            Some(ExplainFormat::Jsonl)
+        );
+        assert_eq!(
+            parse(&["--no-digit=fail"]).unwrap().no_digit,
+            NoDigitPolicy::Fail
+        );
+        assert!(parse(&["--compound", "--no-digit=skip"]).unwrap().compound);
+        assert!(parse(&["--explain=xml"]).is_err());
+        assert!(parse(&["--no-digit"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["a.txt", "b.txt"]).is_err());
+    }
+}
@@ -1,43 +1,591 @@
+pub mod cli;
+mod matcher;
+mod numbers;
+mod report;
+mod vocabulary;
+
 use std::{
-    sync::{Arc, Mutex},
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Debug, Display},
+    io::{self, BufRead},
+    ops,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
     thread,
 };
 
-pub fn concat_first_and_last_digits(line: &str) -> u32 {
-    let mut digits = line.chars().filter(|c| c.is_numeric());
-    let first = digits.next().unwrap_or('0');
-    let last = digits.next_back().unwrap_or(first);
-    let result = format!("{}{}", first, last);
+pub use matcher::{digit_tokens, DigitMatcher, DigitTokens};
+pub use numbers::{number_tokens, NumberToken, NumberWords};
+pub use report::{CalibrationReport, LineReport};
+pub use vocabulary::{DigitVocabulary, VocabularyError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError {
+    NonDecimalNumeral(char),
+    NoDigit,
+}
+
+impl Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonDecimalNumeral(c) => write!(f, "non-decimal numeral `{}`", c),
+            Self::NoDigit => write!(f, "no digit found"),
+        }
+    }
+}
+
+impl Error for CalibrationError {}
+
+const DECIMAL_ZEROS: [u32; 77] = [
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x10D40, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450,
+    0x114D0, 0x11650, 0x116C0, 0x116D0, 0x116DA, 0x11730, 0x118E0, 0x11950, 0x11BF0, 0x11C50,
+    0x11D50, 0x11DA0, 0x11DE0, 0x11F50, 0x16130, 0x16A60, 0x16AC0, 0x16B50, 0x16D70, 0x1CCF0,
+    0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0, 0x1E5F1, 0x1E950,
+    0x1FBF0,
+];
+
+pub fn decimal_digit(c: char) -> Option<u32> {
+    let c = c as u32;
+    let zero = DECIMAL_ZEROS[DECIMAL_ZEROS.partition_point(|&z| z <= c).checked_sub(1)?];
+
+    (c - zero < 10).then_some(c - zero)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumericPolicy {
+    #[default]
+    Skip,
+    Error,
+}
+
+fn decimal_tokens(
+    line: &str,
+    policy: NumericPolicy,
+) -> impl DoubleEndedIterator<Item = Result<DigitToken, CalibrationError>> + '_ {
+    line.char_indices()
+        .filter_map(move |(i, c)| match decimal_digit(c) {
+            Some(value) => Some(Ok(DigitToken {
+                span: i..i + c.len_utf8(),
+                value,
+                source: DigitSource::Digit,
+            })),
+            None if c.is_numeric() && policy == NumericPolicy::Error => {
+                Some(Err(CalibrationError::NonDecimalNumeral(c)))
+            }
+            None => None,
+        })
+}
+
+pub fn digits(
+    line: &str,
+    policy: NumericPolicy,
+) -> impl DoubleEndedIterator<Item = Result<u32, CalibrationError>> + '_ {
+    decimal_tokens(line, policy).map(|token| token.map(|t| t.value))
+}
+
+fn check_numerals(line: &str, policy: NumericPolicy) -> Result<(), CalibrationError> {
+    match policy {
+        NumericPolicy::Skip => Ok(()),
+        NumericPolicy::Error => digits(line, policy).try_for_each(|d| d.map(|_| ())),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitSource {
+    Digit,
+    Word,
+}
+
+impl Display for DigitSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Digit => write!(f, "digit"),
+            Self::Word => write!(f, "word"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitToken {
+    pub span: ops::Range<usize>,
+    pub value: u32,
+    pub source: DigitSource,
+}
+
+pub trait Calibrator {
+    type Error;
+
+    fn calibrate(&self, line: &str) -> Result<Option<u32>, Self::Error>;
+}
+
+impl<F, E> Calibrator for F
+where
+    F: Fn(&str) -> Result<u32, E>,
+{
+    type Error = E;
+
+    fn calibrate(&self, line: &str) -> Result<Option<u32>, E> {
+        self(line).map(Some)
+    }
+}
+
+pub trait Explain: Calibrator {
+    fn first_and_last(&self, line: &str) -> Result<Option<(DigitToken, DigitToken)>, Self::Error>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NoDigitPolicy {
+    #[default]
+    Zero,
+    Skip,
+    Fail,
+}
+
+impl NoDigitPolicy {
+    fn resolve(
+        self,
+        tokens: Option<(DigitToken, DigitToken)>,
+    ) -> Result<Option<u32>, CalibrationError> {
+        match (tokens, self) {
+            (Some((first, last)), _) => Ok(Some(first.value * 10 + last.value)),
+            (None, Self::Zero) => Ok(Some(0)),
+            (None, Self::Skip) => Ok(None),
+            (None, Self::Fail) => Err(CalibrationError::NoDigit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DigitsOnly {
+    pub policy: NumericPolicy,
+    pub no_digit: NoDigitPolicy,
+}
+
+impl DigitsOnly {
+    pub fn with_policy(mut self, policy: NumericPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_no_digit(mut self, no_digit: NoDigitPolicy) -> Self {
+        self.no_digit = no_digit;
+        self
+    }
+}
+
+impl Calibrator for DigitsOnly {
+    type Error = CalibrationError;
+
+    fn calibrate(&self, line: &str) -> Result<Option<u32>, CalibrationError> {
+        self.no_digit.resolve(self.first_and_last(line)?)
+    }
+}
+
+impl Explain for DigitsOnly {
+    fn first_and_last(
+        &self,
+        line: &str,
+    ) -> Result<Option<(DigitToken, DigitToken)>, CalibrationError> {
+        check_numerals(line, self.policy)?;
+
+        let mut tokens = decimal_tokens(line, NumericPolicy::Skip).flatten();
+        let first = tokens.next();
+        let last = tokens.next_back().or_else(|| first.clone());
+
+        Ok(first.zip(last))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitsAndWords {
+    pub policy: NumericPolicy,
+    pub no_digit: NoDigitPolicy,
+    vocabulary: DigitVocabulary,
+}
+
+impl DigitsAndWords {
+    pub fn new(vocabulary: DigitVocabulary) -> Self {
+        Self {
+            policy: NumericPolicy::default(),
+            no_digit: NoDigitPolicy::default(),
+            vocabulary,
+        }
+    }
+
+    pub fn vocabulary(&self) -> &DigitVocabulary {
+        &self.vocabulary
+    }
+
+    pub fn with_policy(mut self, policy: NumericPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_no_digit(mut self, no_digit: NoDigitPolicy) -> Self {
+        self.no_digit = no_digit;
+        self
+    }
+}
+
+impl Default for DigitsAndWords {
+    fn default() -> Self {
+        Self::new(DigitVocabulary::default())
+    }
+}
+
+impl Calibrator for DigitsAndWords {
+    type Error = CalibrationError;
+
+    fn calibrate(&self, line: &str) -> Result<Option<u32>, CalibrationError> {
+        self.no_digit.resolve(self.first_and_last(line)?)
+    }
+}
+
+impl Explain for DigitsAndWords {
+    fn first_and_last(
+        &self,
+        line: &str,
+    ) -> Result<Option<(DigitToken, DigitToken)>, CalibrationError> {
+        check_numerals(line, self.policy)?;
+
+        let matcher = self.vocabulary.matcher();
+
+        Ok(matcher.first(line).zip(matcher.last(line)))
+    }
+}
+
+pub fn concat_first_and_last_digits(line: &str) -> Result<u32, CalibrationError> {
+    Ok(DigitsOnly::default().calibrate(line)?.unwrap_or_default())
+}
+
+pub fn concat_first_and_last_converted_digits(line: &str) -> Result<u32, CalibrationError> {
+    Ok(DigitsAndWords::default()
+        .calibrate(line)?
+        .unwrap_or_default())
+}
+
+pub trait Total: Copy + Debug + Default + Send + From<u32> {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_total {
+    ($($t:ty),*) => {
+        $(
+            impl Total for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_total!(u32, u64, u128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    pub line: usize,
+}
+
+impl Display for OverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sum overflowed at line {}", self.line)
+    }
+}
+
+impl Error for OverflowError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddError<E> {
+    Overflow(OverflowError),
+    Lines(Vec<(usize, E)>),
+    Io { line: usize, kind: io::ErrorKind },
+}
+
+impl<E: Display> Display for AddError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow(e) => write!(f, "{}", e),
+            Self::Lines(failures) => {
+                write!(f, "{} lines failed", failures.len())?;
+
+                for (line, e) in failures {
+                    write!(f, "\nline {}: {}", line, e)?;
+                }
+
+                Ok(())
+            }
+            Self::Io { line, kind } => write!(f, "error reading line {}: {}", line, kind),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for AddError<E> {}
+
+impl<E> From<OverflowError> for AddError<E> {
+    fn from(e: OverflowError) -> Self {
+        Self::Overflow(e)
+    }
+}
+
+struct ChunkSum<T, E> {
+    sum: Result<T, usize>,
+    failures: Vec<(usize, E)>,
+}
+
+fn sum_lines<T: Total, C: Calibrator + ?Sized>(
+    lines: &[&str],
+    first_line: usize,
+    start: T,
+    calibrator: &C,
+) -> ChunkSum<T, C::Error> {
+    let mut sum = Ok(start);
+    let mut failures = vec![];
+
+    for (line, value) in (first_line..).zip(lines.iter().map(|l| calibrator.calibrate(l))) {
+        match value {
+            Ok(Some(value)) => sum = sum.and_then(|s| s.checked_add(T::from(value)).ok_or(line)),
+            Ok(None) => {}
+            Err(e) => failures.push((line, e)),
+        }
+    }
 
-    result.parse::<u32>().unwrap()
+    ChunkSum { sum, failures }
 }
 
-pub fn add_over_lines(
+pub fn add_over_lines<T, C>(
     lines: &[&str],
     worker_count: usize,
-    adder: impl Fn(&str) -> u32 + Send + Copy,
-) -> u32 {
+    calibrator: &C,
+) -> Result<T, AddError<C::Error>>
+where
+    T: Total,
+    C: Calibrator + Sync + ?Sized,
+    C::Error: Send,
+{
     thread::scope(|s| {
-        let chunks = lines.chunks(lines.len() / worker_count + 1);
-        let sum = Arc::new(Mutex::new(0u32));
+        let chunk_size = lines.len() / worker_count.max(1) + 1;
+        let chunks = lines.chunks(chunk_size);
         let mut handles = vec![];
 
-        for chunk in chunks {
-            let sum = Arc::clone(&sum);
-            handles.push(s.spawn(move || {
-                for line in chunk {
-                    let mut sum = sum.lock().unwrap();
-                    *sum += adder(line);
+        for (i, chunk) in chunks.enumerate() {
+            handles.push(
+                s.spawn(move || sum_lines(chunk, i * chunk_size + 1, T::default(), calibrator)),
+            );
+        }
+
+        let mut sums = vec![];
+        let mut failures = vec![];
+
+        for handle in handles {
+            let chunk_sum = handle.join().unwrap();
+            sums.push(chunk_sum.sum);
+            failures.extend(chunk_sum.failures);
+        }
+
+        if !failures.is_empty() {
+            return Err(AddError::Lines(failures));
+        }
+
+        let mut sum = T::default();
+
+        for (i, chunk_sum) in sums.into_iter().enumerate() {
+            sum = match chunk_sum.ok().and_then(|c| sum.checked_add(c)) {
+                Some(sum) => sum,
+                None => {
+                    let chunk = &lines[i * chunk_size..lines.len().min((i + 1) * chunk_size)];
+                    let line = sum_lines(chunk, i * chunk_size + 1, sum, calibrator)
+                        .sum
+                        .unwrap_err();
+
+                    return Err(OverflowError { line }.into());
+                }
+            };
+        }
+
+        Ok(sum)
+    })
+}
+
+const BATCH_SIZE: usize = 4096;
+
+struct Batch {
+    index: usize,
+    first_line: usize,
+    lines: Vec<String>,
+}
+
+struct CalibratedBatch<E> {
+    index: usize,
+    first_line: usize,
+    values: Vec<u32>,
+    failures: Vec<(usize, E)>,
+}
+
+fn read_batches(
+    reader: impl BufRead,
+    sender: mpsc::SyncSender<Batch>,
+    permits: mpsc::Receiver<()>,
+) -> Result<(), (usize, io::Error)> {
+    let send = |batch| permits.recv().is_ok() && sender.send(batch).is_ok();
+    let mut batch = Batch {
+        index: 0,
+        first_line: 1,
+        lines: Vec::with_capacity(BATCH_SIZE),
+    };
+
+    for (number, line) in (1..).zip(reader.lines()) {
+        batch.lines.push(line.map_err(|e| (number, e))?);
+
+        if batch.lines.len() == BATCH_SIZE {
+            let next = Batch {
+                index: batch.index + 1,
+                first_line: number + 1,
+                lines: Vec::with_capacity(BATCH_SIZE),
+            };
+
+            if !send(std::mem::replace(&mut batch, next)) {
+                return Ok(());
+            }
+        }
+    }
+
+    if !batch.lines.is_empty() {
+        send(batch);
+    }
+
+    Ok(())
+}
+
+fn calibrate_batch<C: Calibrator + ?Sized>(
+    batch: Batch,
+    calibrator: &C,
+) -> CalibratedBatch<C::Error> {
+    let mut values = Vec::with_capacity(batch.lines.len());
+    let mut failures = vec![];
+
+    for (line, text) in (batch.first_line..).zip(&batch.lines) {
+        match calibrator.calibrate(text) {
+            Ok(value) => values.push(value.unwrap_or(0)),
+            Err(e) => {
+                values.push(0);
+                failures.push((line, e));
+            }
+        }
+    }
+
+    CalibratedBatch {
+        index: batch.index,
+        first_line: batch.first_line,
+        values,
+        failures,
+    }
+}
+
+pub fn add_over_reader<T, C>(
+    reader: impl BufRead,
+    worker_count: usize,
+    calibrator: &C,
+) -> Result<T, AddError<C::Error>>
+where
+    T: Total,
+    C: Calibrator + Sync + ?Sized,
+    C::Error: Send,
+{
+    let worker_count = worker_count.max(1);
+    let (batch_sender, batch_receiver) = mpsc::sync_channel::<Batch>(worker_count);
+    let (calibrated_sender, calibrated_receiver) = mpsc::sync_channel(worker_count);
+    let (permit_sender, permit_receiver) = mpsc::sync_channel(2 * worker_count);
+    let batch_receiver = Arc::new(Mutex::new(batch_receiver));
+
+    for _ in 0..2 * worker_count {
+        permit_sender.send(()).unwrap();
+    }
+
+    thread::scope(|s| {
+        let mut workers = vec![];
+
+        for _ in 0..worker_count {
+            let batch_receiver = Arc::clone(&batch_receiver);
+            let calibrated_sender = calibrated_sender.clone();
+
+            workers.push(s.spawn(move || loop {
+                let Ok(batch) = batch_receiver.lock().unwrap().recv() else {
+                    break;
+                };
+
+                let calibrated =
+                    panic::catch_unwind(AssertUnwindSafe(|| calibrate_batch(batch, calibrator)));
+
+                match calibrated {
+                    Ok(calibrated) => {
+                        if calibrated_sender.send(Some(calibrated)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(payload) => {
+                        calibrated_sender.send(None).ok();
+                        panic::resume_unwind(payload);
+                    }
                 }
             }));
         }
 
-        for handle in handles {
-            handle.join().unwrap();
+        drop(batch_receiver);
+        drop(calibrated_sender);
+
+        let reducer = s.spawn(move || {
+            let mut pending = BTreeMap::new();
+            let mut next = 0;
+            let mut sum = Ok(T::default());
+            let mut failures = vec![];
+
+            for calibrated in calibrated_receiver {
+                let Some(calibrated) = calibrated else {
+                    break;
+                };
+
+                pending.insert(calibrated.index, calibrated);
+
+                while let Some(calibrated) = pending.remove(&next) {
+                    next += 1;
+                    failures.extend(calibrated.failures);
+
+                    for (line, value) in (calibrated.first_line..).zip(calibrated.values) {
+                        sum = sum.and_then(|s: T| s.checked_add(T::from(value)).ok_or(line));
+                    }
+
+                    permit_sender.send(()).ok();
+                }
+            }
+
+            (sum.map_err(|line| OverflowError { line }), failures)
+        });
+
+        let read = read_batches(reader, batch_sender, permit_receiver);
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let (sum, failures) = reducer.join().unwrap();
+
+        if let Err((line, e)) = read {
+            return Err(AddError::Io {
+                line,
+                kind: e.kind(),
+            });
+        }
+
+        if !failures.is_empty() {
+            return Err(AddError::Lines(failures));
         }
 
-        let result = *sum.lock().unwrap();
-        result
+        Ok(sum?)
     })
 }
 
@@ -47,10 +595,126 @@ mod tests {
 
     #[test]
     fn test_concat_first_and_last_digits() {
-        assert_eq!(concat_first_and_last_digits("one"), 0);
-        assert_eq!(concat_first_and_last_digits("one2three"), 22);
-        assert_eq!(concat_first_and_last_digits("one2three4five"), 24);
-        assert_eq!(concat_first_and_last_digits("one2three4five6seven"), 26);
+        assert_eq!(concat_first_and_last_digits("one"), Ok(0));
+        assert_eq!(concat_first_and_last_digits("one2three"), Ok(22));
+        assert_eq!(concat_first_and_last_digits("one2three4five"), Ok(24));
+        assert_eq!(concat_first_and_last_digits("one2three4five6seven"), Ok(26));
+        assert_eq!(concat_first_and_last_digits("a½b3"), Ok(33));
+        assert_eq!(concat_first_and_last_digits("٣x五y७"), Ok(37));
+        assert_eq!(
+            DigitsOnly::default()
+                .with_policy(NumericPolicy::Error)
+                .calibrate("a½b3"),
+            Err(CalibrationError::NonDecimalNumeral('½'))
+        );
+        assert_eq!(
+            DigitsOnly::default()
+                .with_policy(NumericPolicy::Error)
+                .calibrate("1aⅫb7"),
+            Err(CalibrationError::NonDecimalNumeral('Ⅻ'))
+        );
+    }
+
+    #[test]
+    fn test_decimal_digit() {
+        assert_eq!(decimal_digit('0'), Some(0));
+        assert_eq!(decimal_digit('9'), Some(9));
+        assert_eq!(decimal_digit('٣'), Some(3));
+        assert_eq!(decimal_digit('९'), Some(9));
+        assert_eq!(decimal_digit('７'), Some(7));
+        assert_eq!(decimal_digit('𝟘'), Some(0));
+        assert_eq!(decimal_digit('𝟡'), Some(9));
+        assert_eq!(decimal_digit('½'), None);
+        assert_eq!(decimal_digit('五'), None);
+        assert_eq!(decimal_digit('Ⅻ'), None);
+        assert_eq!(decimal_digit('a'), None);
+        assert_eq!(decimal_digit('/'), None);
+
+        for c in (0..=0x10FFFF).filter_map(char::from_u32) {
+            if let Some(digit) = decimal_digit(c) {
+                assert!(c.is_numeric() && digit < 10, "{:?}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_first_digit_match() {
+        let matcher = DigitMatcher::new(&DigitVocabulary::english());
+
+        assert_eq!(matcher.first("asdfasdfasdf").map(|t| t.value), None);
+        assert_eq!(matcher.first("two1nine").map(|t| t.value), Some(2));
+        assert_eq!(matcher.first("asdf1nine").map(|t| t.value), Some(1));
+        assert_eq!(matcher.first("asdfour").map(|t| t.value), Some(4));
+        assert_eq!(matcher.first("asdf5").map(|t| t.value), Some(5));
+        assert_eq!(matcher.first("7pqrstsixteen").map(|t| t.value), Some(7));
+    }
+
+    #[test]
+    fn test_last_digit_match() {
+        let matcher = DigitMatcher::new(&DigitVocabulary::english());
+
+        assert_eq!(matcher.last("asdfasdfasdf").map(|t| t.value), None);
+        assert_eq!(matcher.last("two1nine").map(|t| t.value), Some(9));
+        assert_eq!(matcher.last("two1asdf").map(|t| t.value), Some(1));
+        assert_eq!(matcher.last("nineteen").map(|t| t.value), Some(9));
+        assert_eq!(matcher.last("5asdf").map(|t| t.value), Some(5));
+        assert_eq!(matcher.last("asdf5").map(|t| t.value), Some(5));
+    }
+
+    #[test]
+    fn test_concat_first_and_last_converted_digits() {
+        assert_eq!(
+            concat_first_and_last_converted_digits("asdfasdfasdf"),
+            Ok(0)
+        );
+        assert_eq!(concat_first_and_last_converted_digits("two1nine"), Ok(29));
+        assert_eq!(
+            concat_first_and_last_converted_digits("eightwothree"),
+            Ok(83)
+        );
+        assert_eq!(
+            concat_first_and_last_converted_digits("abone2threeyz"),
+            Ok(13)
+        );
+        assert_eq!(
+            concat_first_and_last_converted_digits("4asdfasdfasdf"),
+            Ok(44)
+        );
+        assert_eq!(
+            concat_first_and_last_converted_digits("asdfasdfasdf1"),
+            Ok(11)
+        );
+        assert_eq!(
+            concat_first_and_last_converted_digits("twoasdfasdf"),
+            Ok(22)
+        );
+        assert_eq!(
+            concat_first_and_last_converted_digits("asdfasdftwo"),
+            Ok(22)
+        );
+        assert_eq!(
+            concat_first_and_last_converted_digits("7pqrstsixteen"),
+            Ok(76)
+        );
+    }
+
+    #[test]
+    fn test_digits_and_words_vocabularies() {
+        let portuguese = DigitsAndWords::new(DigitVocabulary::portuguese());
+        let spanish = DigitsAndWords::new(DigitVocabulary::spanish());
+
+        assert_eq!(portuguese.calibrate("xtrêsyduas"), Ok(Some(32)));
+        assert_eq!(portuguese.calibrate("éumaçãoseteê"), Ok(Some(17)));
+        assert_eq!(portuguese.calibrate("ççç"), Ok(Some(0)));
+        assert_eq!(spanish.calibrate("nueveñtresdosuno"), Ok(Some(91)));
+        assert_eq!(spanish.calibrate("cuatro٣"), Ok(Some(43)));
+        assert_eq!(DigitsAndWords::default().calibrate("٣abc½"), Ok(Some(33)));
+        assert_eq!(
+            DigitsAndWords::default()
+                .with_policy(NumericPolicy::Error)
+                .calibrate("one½two"),
+            Err(CalibrationError::NonDecimalNumeral('½'))
+        );
     }
 
     #[test]
@@ -62,6 +726,187 @@ treb7uchet"
             .lines()
             .collect();
 
-        assert_eq!(add_over_lines(&lines, 2, concat_first_and_last_digits), 142);
+        assert_eq!(
+            add_over_lines::<u32, _>(&lines, 2, &DigitsOnly::default()),
+            Ok(142)
+        );
+        assert_eq!(
+            add_over_lines::<u32, _>(&lines, 0, &DigitsOnly::default()),
+            Ok(142)
+        );
+    }
+
+    #[test]
+    fn test_add_over_lines_failures() {
+        let lines = ["12", "½3", "45", "x", "6½"];
+        let strict = DigitsOnly::default().with_policy(NumericPolicy::Error);
+
+        assert_eq!(
+            add_over_lines::<u32, _>(&lines, 2, &strict),
+            Err(AddError::Lines(vec![
+                (2, CalibrationError::NonDecimalNumeral('½')),
+                (5, CalibrationError::NonDecimalNumeral('½')),
+            ]))
+        );
+        assert_eq!(
+            add_over_lines::<u32, _>(&lines, 2, &DigitsOnly::default()),
+            Ok(12 + 33 + 45 + 66)
+        );
+    }
+
+    #[test]
+    fn test_no_digit_policy() {
+        let lines = ["1x2", "abc", "", "7"];
+
+        for (no_digit, expected) in [
+            (NoDigitPolicy::Zero, Ok(89)),
+            (NoDigitPolicy::Skip, Ok(89)),
+            (
+                NoDigitPolicy::Fail,
+                Err(AddError::Lines(vec![
+                    (2, CalibrationError::NoDigit),
+                    (3, CalibrationError::NoDigit),
+                ])),
+            ),
+        ] {
+            let calibrator = DigitsAndWords::default().with_no_digit(no_digit);
+            assert_eq!(add_over_lines::<u32, _>(&lines, 2, &calibrator), expected);
+        }
+
+        let digits_only = DigitsOnly::default();
+
+        assert_eq!(digits_only.calibrate("abc"), Ok(Some(0)));
+        assert_eq!(
+            digits_only
+                .with_no_digit(NoDigitPolicy::Skip)
+                .calibrate("abc"),
+            Ok(None)
+        );
+        assert_eq!(
+            digits_only
+                .with_no_digit(NoDigitPolicy::Fail)
+                .calibrate("abc"),
+            Err(CalibrationError::NoDigit)
+        );
+        assert_eq!(
+            digits_only
+                .with_no_digit(NoDigitPolicy::Fail)
+                .calibrate("a4"),
+            Ok(Some(44))
+        );
+    }
+
+    #[test]
+    fn test_add_over_lines_overflow() {
+        let lines = vec!["99"; 10];
+        let max_u32 = |line: &str| Ok::<_, ()>(if line == "max" { u32::MAX } else { 1 });
+
+        assert_eq!(
+            add_over_lines::<u32, _>(&["1", "max", "1"], 2, &max_u32),
+            Err(AddError::Overflow(OverflowError { line: 2 }))
+        );
+        assert_eq!(
+            add_over_lines::<u32, _>(&["max", "1", "1", "1"], 3, &max_u32),
+            Err(AddError::Overflow(OverflowError { line: 2 }))
+        );
+        assert_eq!(
+            add_over_lines::<u64, _>(&["max", "1", "max"], 2, &max_u32),
+            Ok(2 * u32::MAX as u64 + 1)
+        );
+        assert_eq!(
+            add_over_lines::<u128, _>(&lines, 4, &DigitsOnly::default()),
+            Ok(990)
+        );
+    }
+
+    #[test]
+    fn test_add_over_reader() {
+        let input = "1abc2\npqr3stu8vwx\r\na1b2c3d4e5f\ntreb7uchet\n";
+
+        assert_eq!(
+            add_over_reader::<u32, _>(input.as_bytes(), 2, &DigitsOnly::default()),
+            Ok(142)
+        );
+        assert_eq!(
+            add_over_reader::<u32, _>("".as_bytes(), 2, &DigitsOnly::default()),
+            Ok(0)
+        );
+
+        let lines: Vec<String> = (0..3 * BATCH_SIZE + 17)
+            .map(|i| format!("{}x{}", i % 7, i % 10))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let input = lines.join("\n");
+
+        for workers in [1, 3] {
+            assert_eq!(
+                add_over_reader::<u64, _>(input.as_bytes(), workers, &DigitsAndWords::default()),
+                add_over_lines::<u64, _>(&lines, workers, &DigitsAndWords::default())
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_over_reader_errors() {
+        let strict = DigitsOnly::default().with_policy(NumericPolicy::Error);
+        let mut lines = vec!["11"; 2 * BATCH_SIZE];
+        lines[5] = "½";
+        lines[BATCH_SIZE + 1] = "3½";
+
+        assert_eq!(
+            add_over_reader::<u32, _>(lines.join("\n").as_bytes(), 2, &strict),
+            Err(AddError::Lines(vec![
+                (6, CalibrationError::NonDecimalNumeral('½')),
+                (BATCH_SIZE + 2, CalibrationError::NonDecimalNumeral('½')),
+            ]))
+        );
+
+        let max_u32 = |line: &str| Ok::<_, ()>(if line == "max" { u32::MAX } else { 1 });
+        let mut lines = vec!["1"; 2 * BATCH_SIZE];
+        lines[BATCH_SIZE + 3] = "max";
+
+        assert_eq!(
+            add_over_reader::<u32, _>(lines.join("\n").as_bytes(), 2, &max_u32),
+            Err(AddError::Overflow(OverflowError {
+                line: BATCH_SIZE + 4
+            }))
+        );
+
+        assert_eq!(
+            add_over_reader::<u32, _>(&b"12\n34\n\xff\n56"[..], 2, &DigitsOnly::default()),
+            Err(AddError::Io {
+                line: 3,
+                kind: io::ErrorKind::InvalidData
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_over_reader_panic() {
+        let panicking = |line: &str| -> Result<u32, ()> {
+            match line {
+                "x" => panic!("calibrator panicked"),
+                _ => Ok(1),
+            }
+        };
+
+        let all = vec!["x"; 8 * BATCH_SIZE];
+        let mut one = vec!["1"; 20 * BATCH_SIZE];
+        one[5] = "x";
+
+        for lines in [all, one] {
+            let input = lines.join("\n");
+            let (done, finished) = mpsc::channel::<()>();
+            let handle = thread::spawn(move || {
+                let _done = done;
+                add_over_reader::<u32, _>(input.as_bytes(), 2, &panicking)
+            });
+
+            assert_eq!(
+                finished.recv_timeout(std::time::Duration::from_secs(30)),
+                Err(mpsc::RecvTimeoutError::Disconnected)
+            );
+            assert!(handle.join().is_err());
+        }
     }
 }
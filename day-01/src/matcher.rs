@@ -0,0 +1,480 @@
+use std::{collections::VecDeque, str::CharIndices};
+
+use crate::{decimal_digit, DigitSource, DigitToken, DigitVocabulary};
+
+const NONE: u32 = u32::MAX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Automaton {
+    next: Vec<[u32; 256]>,
+    output: Vec<Option<(usize, u32)>>,
+    depth: Vec<usize>,
+    longest: usize,
+}
+
+impl Automaton {
+    fn new(words: impl Iterator<Item = (impl Iterator<Item = u8>, u32)>) -> Self {
+        let mut automaton = Self {
+            next: vec![[NONE; 256]],
+            output: vec![None],
+            depth: vec![0],
+            longest: 0,
+        };
+
+        for (word, value) in words {
+            let mut state = 0;
+            let mut length = 0;
+
+            for byte in word {
+                if automaton.next[state][byte as usize] == NONE {
+                    automaton.next[state][byte as usize] = automaton.next.len() as u32;
+                    automaton.next.push([NONE; 256]);
+                    automaton.output.push(None);
+                    automaton.depth.push(length + 1);
+                }
+
+                state = automaton.next[state][byte as usize] as usize;
+                length += 1;
+            }
+
+            automaton.output[state].get_or_insert((length, value));
+            automaton.longest = automaton.longest.max(length);
+        }
+
+        automaton.link();
+        automaton
+    }
+
+    fn link(&mut self) {
+        let mut fail = vec![0; self.next.len()];
+        let mut queue = VecDeque::new();
+
+        for byte in 0..256 {
+            match self.next[0][byte] {
+                NONE => self.next[0][byte] = 0,
+                child => queue.push_back(child as usize),
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            if self.output[state].is_none() {
+                self.output[state] = self.output[fail[state]];
+            }
+
+            for byte in 0..256 {
+                let fallback = self.next[fail[state]][byte];
+
+                match self.next[state][byte] {
+                    NONE => self.next[state][byte] = fallback,
+                    child => {
+                        fail[child as usize] = fallback as usize;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+    }
+
+    fn words_at(&self, bytes: &[u8]) -> Vec<(usize, u32)> {
+        let mut state = 0;
+        let mut words = vec![];
+
+        for (length, &byte) in (1..).zip(bytes.iter().take(self.longest)) {
+            state = self.next[state][byte as usize] as usize;
+
+            if self.depth[state] != length {
+                break;
+            }
+
+            if let Some((word_length, value)) = self.output[state] {
+                if word_length == length {
+                    words.push((length, value));
+                }
+            }
+        }
+
+        words
+    }
+
+    fn scan(
+        &self,
+        chars: impl Iterator<Item = char>,
+        reverse: bool,
+    ) -> Option<(usize, usize, u32, DigitSource)> {
+        let mut state = 0;
+        let mut fed = 0;
+        let mut best = None;
+        let mut buffer = [0; 4];
+
+        for c in chars {
+            if let Some(digit) = decimal_digit(c) {
+                return best.or(Some((fed, c.len_utf8(), digit, DigitSource::Digit)));
+            }
+
+            let bytes = c.encode_utf8(&mut buffer).as_bytes();
+
+            for i in 0..bytes.len() {
+                let byte = if reverse {
+                    bytes[bytes.len() - 1 - i]
+                } else {
+                    bytes[i]
+                };
+
+                state = self.next[state][byte as usize] as usize;
+                fed += 1;
+
+                if let Some((length, value)) = self.output[state] {
+                    let start = fed - length;
+
+                    if reverse {
+                        return Some((start, length, value, DigitSource::Word));
+                    }
+
+                    if best.is_none_or(|(best_start, ..)| start < best_start) {
+                        best = Some((start, length, value, DigitSource::Word));
+                    }
+                }
+            }
+
+            if let Some((start, ..)) = best {
+                if fed + 1 >= start + self.longest {
+                    return best;
+                }
+            }
+        }
+
+        best
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitMatcher {
+    forward: Automaton,
+    backward: Automaton,
+}
+
+impl DigitMatcher {
+    pub fn new(vocabulary: &DigitVocabulary) -> Self {
+        Self {
+            forward: Automaton::new(vocabulary.words().map(|(w, v)| (w.bytes(), v))),
+            backward: Automaton::new(vocabulary.words().map(|(w, v)| (w.bytes().rev(), v))),
+        }
+    }
+
+    pub fn first(&self, line: &str) -> Option<DigitToken> {
+        let (start, length, value, source) = self.forward.scan(line.chars(), false)?;
+
+        Some(DigitToken {
+            span: start..start + length,
+            value,
+            source,
+        })
+    }
+
+    pub fn tokens<'a>(&'a self, line: &'a str) -> DigitTokens<'a> {
+        DigitTokens {
+            line,
+            automaton: &self.forward,
+            positions: line.char_indices(),
+            front: VecDeque::new(),
+            back: VecDeque::new(),
+        }
+    }
+
+    pub fn last(&self, line: &str) -> Option<DigitToken> {
+        let (distance, length, value, source) = self.backward.scan(line.chars().rev(), true)?;
+        let end = line.len() - distance;
+
+        Some(DigitToken {
+            span: end - length..end,
+            value,
+            source,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DigitTokens<'a> {
+    line: &'a str,
+    automaton: &'a Automaton,
+    positions: CharIndices<'a>,
+    front: VecDeque<DigitToken>,
+    back: VecDeque<DigitToken>,
+}
+
+impl DigitTokens<'_> {
+    fn tokens_at(&self, start: usize, c: char) -> VecDeque<DigitToken> {
+        if let Some(value) = decimal_digit(c) {
+            return VecDeque::from([DigitToken {
+                span: start..start + c.len_utf8(),
+                value,
+                source: DigitSource::Digit,
+            }]);
+        }
+
+        self.automaton
+            .words_at(&self.line.as_bytes()[start..])
+            .into_iter()
+            .map(|(length, value)| DigitToken {
+                span: start..start + length,
+                value,
+                source: DigitSource::Word,
+            })
+            .collect()
+    }
+}
+
+impl Iterator for DigitTokens<'_> {
+    type Item = DigitToken;
+
+    fn next(&mut self) -> Option<DigitToken> {
+        while self.front.is_empty() {
+            match self.positions.next() {
+                Some((start, c)) => self.front = self.tokens_at(start, c),
+                None => return self.back.pop_front(),
+            }
+        }
+
+        self.front.pop_front()
+    }
+}
+
+impl DoubleEndedIterator for DigitTokens<'_> {
+    fn next_back(&mut self) -> Option<DigitToken> {
+        while self.back.is_empty() {
+            match self.positions.next_back() {
+                Some((start, c)) => self.back = self.tokens_at(start, c),
+                None => return self.front.pop_back(),
+            }
+        }
+
+        self.back.pop_back()
+    }
+}
+
+pub fn digit_tokens<'a>(line: &'a str, vocabulary: &'a DigitVocabulary) -> DigitTokens<'a> {
+    vocabulary.matcher().tokens(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_first(input: &str, vocabulary: &DigitVocabulary) -> Option<u32> {
+        input
+            .char_indices()
+            .find_map(|(i, c)| decimal_digit(c).or_else(|| vocabulary.word_at(&input[i..])))
+    }
+
+    fn naive_last(input: &str, vocabulary: &DigitVocabulary) -> Option<u32> {
+        input.char_indices().rev().find_map(|(i, c)| {
+            decimal_digit(c).or_else(|| vocabulary.word_ending(&input[..i + c.len_utf8()]))
+        })
+    }
+
+    fn naive_tokens(line: &str, vocabulary: &DigitVocabulary) -> Vec<DigitToken> {
+        let mut tokens = vec![];
+
+        for (start, c) in line.char_indices() {
+            if let Some(value) = decimal_digit(c) {
+                tokens.push(DigitToken {
+                    span: start..start + c.len_utf8(),
+                    value,
+                    source: DigitSource::Digit,
+                });
+
+                continue;
+            }
+
+            let mut words: Vec<_> = vocabulary
+                .words()
+                .filter(|(w, _)| line[start..].starts_with(w))
+                .collect();
+
+            words.sort_by_key(|(w, _)| w.len());
+            tokens.extend(words.into_iter().map(|(w, value)| DigitToken {
+                span: start..start + w.len(),
+                value,
+                source: DigitSource::Word,
+            }));
+        }
+
+        tokens
+    }
+
+    const ALPHABET: &str = "onetwhrfuivsxgzêçãdaceo1½٣";
+
+    fn random_lines(seed: u64, alphabet: &str) -> Vec<String> {
+        let mut state = seed;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let alphabet: Vec<char> = alphabet.chars().collect();
+
+        (0..2000)
+            .map(|_| {
+                (0..next() % 24)
+                    .map(|_| alphabet[next() as usize % alphabet.len()])
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let matcher = DigitMatcher::new(&DigitVocabulary::english());
+
+        assert_eq!(matcher.first("eightwothree").map(|t| t.value), Some(8));
+        assert_eq!(matcher.last("eightwothree").map(|t| t.value), Some(3));
+        assert_eq!(matcher.first("xeightwo").map(|t| t.value), Some(8));
+        assert_eq!(matcher.last("xeightwo").map(|t| t.value), Some(2));
+        assert_eq!(matcher.first("oneight").map(|t| t.value), Some(1));
+        assert_eq!(matcher.last("oneight").map(|t| t.value), Some(8));
+        assert_eq!(matcher.first("7pqrstsixteen").map(|t| t.value), Some(7));
+        assert_eq!(matcher.last("7pqrstsixteen").map(|t| t.value), Some(6));
+        assert_eq!(matcher.first("zerone٣").map(|t| t.value), Some(0));
+        assert_eq!(matcher.last("zerone").map(|t| t.value), Some(1));
+        assert_eq!(matcher.first("asdf").map(|t| t.value), None);
+        assert_eq!(matcher.last("").map(|t| t.value), None);
+
+        let overlapping =
+            DigitVocabulary::from_words([("abcd", 1), ("bc", 2), ("cde", 3)]).unwrap();
+        let matcher = DigitMatcher::new(&overlapping);
+
+        assert_eq!(matcher.first("xabcde").map(|t| t.value), Some(1));
+        assert_eq!(matcher.last("xabcde").map(|t| t.value), Some(3));
+        assert_eq!(matcher.first("xabcx").map(|t| t.value), Some(2));
+        assert_eq!(matcher.last("abcdx").map(|t| t.value), Some(2));
+        assert_eq!(
+            matcher.last("abcd"),
+            Some(DigitToken {
+                span: 1..3,
+                value: 2,
+                source: DigitSource::Word
+            })
+        );
+
+        let matcher = DigitMatcher::new(&DigitVocabulary::portuguese());
+
+        assert_eq!(
+            matcher.first("çtrêsx٣"),
+            Some(DigitToken {
+                span: 2..7,
+                value: 3,
+                source: DigitSource::Word
+            })
+        );
+        assert_eq!(
+            matcher.last("çtrêsx٣ç"),
+            Some(DigitToken {
+                span: 8..10,
+                value: 3,
+                source: DigitSource::Digit
+            })
+        );
+        assert_eq!(
+            matcher.last("xumaç"),
+            Some(DigitToken {
+                span: 1..4,
+                value: 1,
+                source: DigitSource::Word
+            })
+        );
+    }
+
+    #[test]
+    fn test_matches_naive_scan() {
+        for vocabulary in [
+            DigitVocabulary::english(),
+            DigitVocabulary::portuguese(),
+            DigitVocabulary::spanish(),
+        ] {
+            let matcher = DigitMatcher::new(&vocabulary);
+
+            for line in random_lines(0x9e3779b97f4a7c15, ALPHABET) {
+                assert_eq!(
+                    matcher.first(&line).map(|t| t.value),
+                    naive_first(&line, &vocabulary),
+                    "{}",
+                    line
+                );
+                assert_eq!(
+                    matcher.last(&line).map(|t| t.value),
+                    naive_last(&line, &vocabulary),
+                    "{}",
+                    line
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_digit_tokens() {
+        let english = DigitVocabulary::english();
+        let spans = |tokens: &mut dyn Iterator<Item = DigitToken>| {
+            tokens.map(|t| (t.span, t.value)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            spans(&mut digit_tokens("xoneightwo7", &english)),
+            vec![(1..4, 1), (3..8, 8), (7..10, 2), (10..11, 7)]
+        );
+        assert_eq!(
+            spans(&mut digit_tokens("xoneightwo7", &english).rev()),
+            vec![(10..11, 7), (7..10, 2), (3..8, 8), (1..4, 1)]
+        );
+
+        let portuguese = DigitVocabulary::portuguese();
+        let matcher = DigitMatcher::new(&portuguese);
+
+        assert_eq!(
+            spans(&mut matcher.tokens("umados٣")),
+            vec![(0..2, 1), (0..3, 1), (6..8, 3)]
+        );
+
+        let mut tokens = matcher.tokens("umatrês");
+        assert_eq!(tokens.next_back().map(|t| t.span), Some(3..8));
+        assert_eq!(tokens.next().map(|t| t.span), Some(0..2));
+        assert_eq!(tokens.next_back().map(|t| t.span), Some(0..3));
+        assert_eq!(tokens.next(), None);
+        assert_eq!(tokens.next_back(), None);
+
+        let line = "sevenine 3 twothree";
+        assert_eq!(
+            digit_tokens(line, &english).map(|t| t.value).sum::<u32>(),
+            24
+        );
+        assert_eq!(digit_tokens(line, &english).count(), 5);
+        assert_eq!(digit_tokens("", &english).next(), None);
+    }
+
+    #[test]
+    fn test_tokens_match_first_and_last() {
+        let overlapping =
+            DigitVocabulary::from_words([("abcd", 1), ("bc", 2), ("cde", 3), ("ab", 4)]).unwrap();
+
+        for (vocabulary, alphabet) in [
+            (DigitVocabulary::english(), ALPHABET),
+            (DigitVocabulary::portuguese(), ALPHABET),
+            (DigitVocabulary::spanish(), ALPHABET),
+            (overlapping, "abcdex1"),
+        ] {
+            let matcher = DigitMatcher::new(&vocabulary);
+
+            for line in random_lines(0x2545f4914f6cdd1d, alphabet) {
+                let tokens: Vec<_> = digit_tokens(&line, &vocabulary).collect();
+                let mut reversed: Vec<_> = matcher.tokens(&line).rev().collect();
+                reversed.reverse();
+
+                assert_eq!(tokens, naive_tokens(&line, &vocabulary), "{}", line);
+                assert_eq!(tokens, reversed, "{}", line);
+                assert_eq!(tokens.first(), matcher.first(&line).as_ref(), "{}", line);
+                assert_eq!(tokens.last(), matcher.last(&line).as_ref(), "{}", line);
+            }
+        }
+    }
+}
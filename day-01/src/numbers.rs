@@ -0,0 +1,315 @@
+use std::ops;
+
+use crate::{
+    check_numerals, decimal_digit, CalibrationError, Calibrator, DigitSource, DigitToken, Explain,
+    NoDigitPolicy, NumericPolicy,
+};
+
+const UNITS: [(&str, u64); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const TEENS: [(&str, u64); 10] = [
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+    ("sixteen", 16),
+    ("seventeen", 17),
+    ("eighteen", 18),
+    ("nineteen", 19),
+];
+
+const TENS: [(&str, u64); 8] = [
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+];
+
+const SCALES: [(&str, u64); 2] = [("million", 1_000_000), ("thousand", 1_000)];
+
+type Parse = Option<(u64, usize)>;
+
+fn longest(candidates: impl IntoIterator<Item = (u64, usize)>) -> Parse {
+    candidates
+        .into_iter()
+        .fold(None, |best: Parse, (value, end)| match best {
+            Some((_, best_end)) if best_end >= end => best,
+            _ => Some((value, end)),
+        })
+}
+
+fn word(s: &str, pos: usize, words: &[(&str, u64)]) -> Parse {
+    longest(
+        words
+            .iter()
+            .filter(|(w, _)| s[pos..].starts_with(w))
+            .map(|(w, v)| (*v, pos + w.len())),
+    )
+}
+
+fn keyword(s: &str, pos: usize, keyword: &str) -> Option<usize> {
+    s[pos..].starts_with(keyword).then_some(pos + keyword.len())
+}
+
+fn separated(s: &str, pos: usize) -> impl Iterator<Item = usize> {
+    let separator = matches!(s.as_bytes().get(pos), Some(b' ' | b'-'));
+    [Some(pos), separator.then_some(pos + 1)]
+        .into_iter()
+        .flatten()
+}
+
+fn joined(s: &str, pos: usize) -> Vec<usize> {
+    let mut positions: Vec<usize> = separated(s, pos).collect();
+
+    for p in separated(s, pos) {
+        if let Some(end) = keyword(s, p, "and") {
+            positions.extend(separated(s, end));
+        }
+    }
+
+    positions
+}
+
+fn below_hundred(s: &str, pos: usize) -> Parse {
+    let mut candidates = vec![];
+
+    candidates.extend(word(s, pos, &TEENS));
+    candidates.extend(word(s, pos, &UNITS));
+
+    if let Some((tens, end)) = word(s, pos, &TENS) {
+        candidates.push((tens, end));
+
+        for p in separated(s, end) {
+            candidates.extend(word(s, p, &UNITS).map(|(unit, end)| (tens + unit, end)));
+        }
+    }
+
+    longest(candidates)
+}
+
+fn group(s: &str, pos: usize) -> Parse {
+    let mut candidates = vec![];
+
+    candidates.extend(below_hundred(s, pos));
+
+    if let Some((unit, end)) = word(s, pos, &UNITS) {
+        for p in separated(s, end) {
+            if let Some(end) = keyword(s, p, "hundred") {
+                candidates.push((unit * 100, end));
+
+                for p in joined(s, end) {
+                    candidates
+                        .extend(below_hundred(s, p).map(|(rest, end)| (unit * 100 + rest, end)));
+                }
+            }
+        }
+    }
+
+    longest(candidates)
+}
+
+fn scaled(s: &str, pos: usize, scales: &[(&str, u64)]) -> Parse {
+    let Some(((name, factor), smaller)) = scales.split_first() else {
+        return group(s, pos);
+    };
+
+    let mut candidates = vec![];
+
+    candidates.extend(scaled(s, pos, smaller));
+
+    if let Some((count, end)) = group(s, pos) {
+        for p in separated(s, end) {
+            if let Some(end) = keyword(s, p, name) {
+                candidates.push((count * factor, end));
+
+                for p in joined(s, end) {
+                    candidates.extend(
+                        scaled(s, p, smaller).map(|(rest, end)| (count * factor + rest, end)),
+                    );
+                }
+            }
+        }
+    }
+
+    longest(candidates)
+}
+
+fn number(s: &str, pos: usize) -> Parse {
+    longest(
+        keyword(s, pos, "zero")
+            .map(|end| (0, end))
+            .into_iter()
+            .chain(scaled(s, pos, &SCALES)),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberToken {
+    pub span: ops::Range<usize>,
+    pub value: u64,
+    pub source: DigitSource,
+}
+
+impl NumberToken {
+    pub fn first_digit(&self) -> u32 {
+        let mut value = self.value;
+
+        while value >= 10 {
+            value /= 10;
+        }
+
+        value as u32
+    }
+
+    pub fn last_digit(&self) -> u32 {
+        (self.value % 10) as u32
+    }
+}
+
+pub fn number_tokens(line: &str) -> Vec<NumberToken> {
+    let mut tokens = vec![];
+    let mut covered = 0;
+
+    for (start, c) in line.char_indices() {
+        let token = match decimal_digit(c) {
+            Some(value) => NumberToken {
+                span: start..start + c.len_utf8(),
+                value: value as u64,
+                source: DigitSource::Digit,
+            },
+            None => match number(line, start) {
+                Some((value, end)) => NumberToken {
+                    span: start..end,
+                    value,
+                    source: DigitSource::Word,
+                },
+                None => continue,
+            },
+        };
+
+        if token.span.end > covered {
+            covered = token.span.end;
+            tokens.push(token);
+        }
+    }
+
+    tokens
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberWords {
+    pub policy: NumericPolicy,
+    pub no_digit: NoDigitPolicy,
+}
+
+impl NumberWords {
+    pub fn with_policy(mut self, policy: NumericPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_no_digit(mut self, no_digit: NoDigitPolicy) -> Self {
+        self.no_digit = no_digit;
+        self
+    }
+}
+
+impl Calibrator for NumberWords {
+    type Error = CalibrationError;
+
+    fn calibrate(&self, line: &str) -> Result<Option<u32>, CalibrationError> {
+        self.no_digit.resolve(self.first_and_last(line)?)
+    }
+}
+
+impl Explain for NumberWords {
+    fn first_and_last(
+        &self,
+        line: &str,
+    ) -> Result<Option<(DigitToken, DigitToken)>, CalibrationError> {
+        check_numerals(line, self.policy)?;
+
+        let tokens = number_tokens(line);
+        let first = tokens.first().map(|t| DigitToken {
+            span: t.span.clone(),
+            value: t.first_digit(),
+            source: t.source,
+        });
+        let last = tokens.last().map(|t| DigitToken {
+            span: t.span.clone(),
+            value: t.last_digit(),
+            source: t.source,
+        });
+
+        Ok(first.zip(last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(line: &str) -> Vec<u64> {
+        number_tokens(line).into_iter().map(|t| t.value).collect()
+    }
+
+    #[test]
+    fn test_number_tokens() {
+        assert_eq!(values("nineteen"), [19]);
+        assert_eq!(values("twenty-one"), [21]);
+        assert_eq!(values("twentyone"), [21]);
+        assert_eq!(values("one hundred"), [100]);
+        assert_eq!(values("three hundred and forty-two"), [342]);
+        assert_eq!(values("twelve thousand five hundred six"), [12_506]);
+        assert_eq!(values("ninety-nine thousand and one"), [99_001]);
+        assert_eq!(values("one hundred thousand"), [100_000]);
+        assert_eq!(values("two million seventy"), [2_000_070]);
+        assert_eq!(values("eightwothree"), [8, 2, 3]);
+        assert_eq!(values("onetwo"), [1, 2]);
+        assert_eq!(values("xsevenzero4fifty"), [7, 0, 4, 50]);
+        assert_eq!(values("hundred thousand"), [] as [u64; 0]);
+        assert_eq!(values("sixty and"), [60]);
+        assert_eq!(
+            number_tokens("a forty-two")[0],
+            NumberToken {
+                span: 2..11,
+                value: 42,
+                source: DigitSource::Word
+            }
+        );
+    }
+
+    #[test]
+    fn test_number_words() {
+        let calibrator = NumberWords::default();
+
+        assert_eq!(calibrator.calibrate("nineteen"), Ok(Some(19)));
+        assert_eq!(calibrator.calibrate("xone hundred"), Ok(Some(10)));
+        assert_eq!(calibrator.calibrate("seventy-three and 5"), Ok(Some(75)));
+        assert_eq!(calibrator.calibrate("4 twenty"), Ok(Some(40)));
+        assert_eq!(calibrator.calibrate("eightwo"), Ok(Some(82)));
+        assert_eq!(calibrator.calibrate("abc"), Ok(Some(0)));
+        assert_eq!(
+            calibrator
+                .with_no_digit(NoDigitPolicy::Fail)
+                .calibrate("abc"),
+            Err(CalibrationError::NoDigit)
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::{Display, Write};
+
+use crate::{DigitToken, Explain};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReport<E> {
+    pub line: usize,
+    pub text: String,
+    pub tokens: Option<(DigitToken, DigitToken)>,
+    pub value: Result<Option<u32>, E>,
+}
+
+impl<E> LineReport<E> {
+    pub fn value(&self) -> Option<u32> {
+        self.value.as_ref().ok().copied().flatten()
+    }
+
+    pub fn has_digit(&self) -> bool {
+        self.tokens.is_some()
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        matches!(self.value, Ok(None))
+    }
+
+    fn describe(&self, token: &DigitToken) -> String {
+        format!(
+            "{} {}..{} `{}`",
+            token.source,
+            token.span.start,
+            token.span.end,
+            &self.text[token.span.clone()]
+        )
+    }
+
+    fn json_token(&self, token: &DigitToken) -> String {
+        format!(
+            "{{\"start\":{},\"end\":{},\"source\":\"{}\",\"text\":{},\"value\":{}}}",
+            token.span.start,
+            token.span.end,
+            token.source,
+            json_string(&self.text[token.span.clone()]),
+            token.value
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationReport<E> {
+    lines: Vec<LineReport<E>>,
+}
+
+impl<E: Display> CalibrationReport<E> {
+    pub fn new<'a, C>(lines: impl IntoIterator<Item = &'a str>, calibrator: &C) -> Self
+    where
+        C: Explain<Error = E> + ?Sized,
+    {
+        let lines = (1..)
+            .zip(lines)
+            .map(|(line, text)| LineReport {
+                line,
+                text: text.to_string(),
+                tokens: calibrator.first_and_last(text).ok().flatten(),
+                value: calibrator.calibrate(text),
+            })
+            .collect();
+
+        Self { lines }
+    }
+
+    pub fn lines(&self) -> &[LineReport<E>] {
+        &self.lines
+    }
+
+    pub fn no_digit_lines(&self) -> impl Iterator<Item = &LineReport<E>> {
+        self.lines
+            .iter()
+            .filter(|l| l.value.is_ok() && !l.has_digit())
+    }
+
+    pub fn failed_lines(&self) -> impl Iterator<Item = &LineReport<E>> {
+        self.lines.iter().filter(|l| l.value.is_err())
+    }
+
+    pub fn total(&self) -> u64 {
+        self.lines
+            .iter()
+            .filter_map(|l| l.value())
+            .map(u64::from)
+            .sum()
+    }
+
+    pub fn to_table(&self) -> String {
+        let rows: Vec<[String; 4]> = self
+            .lines
+            .iter()
+            .map(|l| {
+                let value = match &l.value {
+                    Ok(Some(value)) => value.to_string(),
+                    Ok(None) => "skip".to_string(),
+                    Err(_) => "-".to_string(),
+                };
+
+                let (first, last) = match (&l.tokens, &l.value) {
+                    (_, Err(e)) => (format!("error: {}", e), String::new()),
+                    (Some((first, last)), _) => (l.describe(first), l.describe(last)),
+                    (None, _) => ("no digit".to_string(), String::new()),
+                };
+
+                [l.line.to_string(), value, first, last]
+            })
+            .collect();
+
+        let header = ["line", "value", "first", "last"].map(String::from);
+        let mut widths = header.clone().map(|h| h.chars().count());
+
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut table = String::new();
+
+        for row in [header].iter().chain(&rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+
+            writeln!(table, "{}", cells.join("  ").trim_end()).unwrap();
+        }
+
+        write!(
+            table,
+            "total {} over {} lines, {} without digits, {} failed",
+            self.total(),
+            self.lines.len(),
+            self.no_digit_lines().count(),
+            self.failed_lines().count()
+        )
+        .unwrap();
+
+        table
+    }
+
+    pub fn to_jsonl(&self) -> String {
+        let mut jsonl = String::new();
+
+        for l in &self.lines {
+            let (first, last) = match &l.tokens {
+                Some((first, last)) => (l.json_token(first), l.json_token(last)),
+                None => ("null".to_string(), "null".to_string()),
+            };
+
+            let error = match &l.value {
+                Ok(_) => "null".to_string(),
+                Err(e) => json_string(&e.to_string()),
+            };
+
+            writeln!(
+                jsonl,
+                "{{\"line\":{},\"text\":{},\"value\":{},\"skipped\":{},\"first\":{},\"last\":{},\"error\":{}}}",
+                l.line,
+                json_string(&l.text),
+                l.value().map_or("null".to_string(), |v| v.to_string()),
+                l.is_skipped(),
+                first,
+                last,
+                error
+            )
+            .unwrap();
+        }
+
+        jsonl
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DigitsAndWords, DigitsOnly, NoDigitPolicy, NumericPolicy};
+
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let report = CalibrationReport::new(["two1nine", "abc", "x7"], &DigitsAndWords::default());
+
+        assert_eq!(
+            report.to_table(),
+            "line  value  first            last\n\
+             1     29     word 0..3 `two`  word 4..8 `nine`\n\
+             2     0      no digit\n\
+             3     77     digit 1..2 `7`   digit 1..2 `7`\n\
+             total 106 over 3 lines, 1 without digits, 0 failed"
+        );
+        assert_eq!(
+            report.no_digit_lines().map(|l| l.line).collect::<Vec<_>>(),
+            [2]
+        );
+
+        let calibrator = DigitsOnly::default().with_no_digit(NoDigitPolicy::Skip);
+        let report = CalibrationReport::new(["1", "x"], &calibrator);
+
+        assert!(report.to_table().contains("\n2     skip   no digit\n"));
+
+        let calibrator = DigitsOnly::default().with_no_digit(NoDigitPolicy::Fail);
+        let report = CalibrationReport::new(["1", "x"], &calibrator);
+
+        assert!(report
+            .to_table()
+            .contains("\n2     -      error: no digit found\n"));
+        assert_eq!(report.failed_lines().count(), 1);
+        assert_eq!(report.no_digit_lines().count(), 0);
+    }
+
+    #[test]
+    fn test_jsonl() {
+        let calibrator = DigitsOnly::default().with_policy(NumericPolicy::Error);
+        let report = CalibrationReport::new(["a\"1\\", "½", ""], &calibrator);
+
+        assert_eq!(
+            report.to_jsonl(),
+            "{\"line\":1,\"text\":\"a\\\"1\\\\\",\"value\":11,\"skipped\":false,\
+             \"first\":{\"start\":2,\"end\":3,\"source\":\"digit\",\"text\":\"1\",\"value\":1},\
+             \"last\":{\"start\":2,\"end\":3,\"source\":\"digit\",\"text\":\"1\",\"value\":1},\
+             \"error\":null}\n\
+             {\"line\":2,\"text\":\"½\",\"value\":null,\"skipped\":false,\"first\":null,\"last\":null,\
+             \"error\":\"non-decimal numeral `½`\"}\n\
+             {\"line\":3,\"text\":\"\",\"value\":0,\"skipped\":false,\"first\":null,\"last\":null,\"error\":null}\n"
+        );
+        assert_eq!(json_string("\u{1}\t"), "\"\\u0001\\t\"");
+    }
+}
@@ -0,0 +1,298 @@
+use std::{error::Error, fmt::Display, fs, path::Path, str::FromStr, sync::OnceLock};
+
+use crate::{decimal_digit, DigitMatcher};
+
+const ENGLISH: [(&str, u32); 10] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const PORTUGUESE: [(&str, u32); 12] = [
+    ("zero", 0),
+    ("um", 1),
+    ("uma", 1),
+    ("dois", 2),
+    ("duas", 2),
+    ("três", 3),
+    ("quatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("sete", 7),
+    ("oito", 8),
+    ("nove", 9),
+];
+
+const SPANISH: [(&str, u32); 11] = [
+    ("cero", 0),
+    ("uno", 1),
+    ("una", 1),
+    ("dos", 2),
+    ("tres", 3),
+    ("cuatro", 4),
+    ("cinco", 5),
+    ("seis", 6),
+    ("siete", 7),
+    ("ocho", 8),
+    ("nueve", 9),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VocabularyError {
+    message: String,
+    line: Option<usize>,
+}
+
+impl VocabularyError {
+    fn new(message: String) -> Self {
+        Self {
+            message,
+            line: None,
+        }
+    }
+
+    fn at(line: usize, message: String) -> Self {
+        Self {
+            message,
+            line: Some(line),
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+}
+
+impl Display for VocabularyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error reading digit vocabulary: {}", self.message)?;
+
+        match self.line {
+            Some(line) => write!(f, " at line {}", line),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for VocabularyError {}
+
+#[derive(Debug, Clone)]
+pub struct DigitVocabulary {
+    words: Vec<(String, u32)>,
+    matcher: OnceLock<DigitMatcher>,
+}
+
+impl PartialEq for DigitVocabulary {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl Eq for DigitVocabulary {}
+
+impl DigitVocabulary {
+    pub fn from_words<S: Into<String>>(
+        words: impl IntoIterator<Item = (S, u32)>,
+    ) -> Result<Self, VocabularyError> {
+        let mut vocabulary = Self {
+            words: vec![],
+            matcher: OnceLock::new(),
+        };
+
+        for (word, value) in words {
+            vocabulary.insert(word.into(), value)?;
+        }
+
+        Ok(vocabulary)
+    }
+
+    pub fn english() -> Self {
+        Self::from_words(ENGLISH).unwrap()
+    }
+
+    pub fn portuguese() -> Self {
+        Self::from_words(PORTUGUESE).unwrap()
+    }
+
+    pub fn spanish() -> Self {
+        Self::from_words(SPANISH).unwrap()
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, VocabularyError> {
+        let path = path.as_ref();
+
+        fs::read_to_string(path)
+            .map_err(|e| VocabularyError::new(format!("{}: {}", path.display(), e)))?
+            .parse()
+    }
+
+    fn insert(&mut self, word: String, value: u32) -> Result<(), VocabularyError> {
+        if word.is_empty()
+            || word
+                .chars()
+                .any(|c| c.is_whitespace() || decimal_digit(c).is_some())
+        {
+            return Err(VocabularyError::new(format!("invalid word `{}`", word)));
+        }
+
+        if value > 9 {
+            return Err(VocabularyError::new(format!(
+                "value {} of `{}` is not a digit",
+                value, word
+            )));
+        }
+
+        match self.words.iter().find(|(w, _)| *w == word) {
+            Some((_, v)) if *v != value => Err(VocabularyError::new(format!(
+                "`{}` is both {} and {}",
+                word, v, value
+            ))),
+            Some(_) => Ok(()),
+            None => {
+                self.words.push((word, value));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words.iter().map(|(w, v)| (w.as_str(), *v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub(crate) fn matcher(&self) -> &DigitMatcher {
+        self.matcher.get_or_init(|| DigitMatcher::new(self))
+    }
+
+    pub fn word_at(&self, s: &str) -> Option<u32> {
+        self.words().find(|(w, _)| s.starts_with(w)).map(|(_, v)| v)
+    }
+
+    pub fn word_ending(&self, s: &str) -> Option<u32> {
+        self.words().find(|(w, _)| s.ends_with(w)).map(|(_, v)| v)
+    }
+}
+
+impl Default for DigitVocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl FromStr for DigitVocabulary {
+    type Err = VocabularyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut vocabulary = Self {
+            words: vec![],
+            matcher: OnceLock::new(),
+        };
+
+        for (number, line) in (1..).zip(s.trim_start_matches('\u{feff}').lines()) {
+            let line = line.split('#').next().unwrap();
+            let mut tokens = line.split_whitespace();
+
+            let value = match tokens.next() {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let value = value
+                .parse::<u32>()
+                .map_err(|_| VocabularyError::at(number, format!("invalid digit `{}`", value)))?;
+
+            let mut words = tokens.peekable();
+
+            if words.peek().is_none() {
+                return Err(VocabularyError::at(
+                    number,
+                    format!("no words for digit {}", value),
+                ));
+            }
+
+            for word in words {
+                vocabulary
+                    .insert(word.to_string(), value)
+                    .map_err(|e| VocabularyError::at(number, e.message))?;
+            }
+        }
+
+        Ok(vocabulary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let vocabulary = DigitVocabulary::from_str(
+            "# portuguese, partial\n\n1 um uma\r\n2 dois duas # feminine\n3 três\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vocabulary.words().collect::<Vec<_>>(),
+            vec![("um", 1), ("uma", 1), ("dois", 2), ("duas", 2), ("três", 3)]
+        );
+        assert_eq!(
+            vocabulary,
+            DigitVocabulary::from_words(PORTUGUESE[1..6].to_vec()).unwrap()
+        );
+
+        let error = DigitVocabulary::from_str("1 um\nx dois").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+
+        let error = DigitVocabulary::from_str("1 um\n\n2").unwrap_err();
+        assert_eq!(error.line(), Some(3));
+
+        let error = DigitVocabulary::from_str("12 doze").unwrap_err();
+        assert_eq!(error.line(), Some(1));
+
+        assert!(DigitVocabulary::from_str("1 dos\n2 dos").is_err());
+        assert!(DigitVocabulary::from_words([("", 1)]).is_err());
+        assert!(DigitVocabulary::from_words([("4x", 4)]).is_err());
+        assert!(DigitVocabulary::from_str("# nothing here")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("day-01-vocabulary-{}", std::process::id()));
+        fs::write(&path, "0 cero\n1 uno una\n").unwrap();
+
+        let vocabulary = DigitVocabulary::from_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(vocabulary.unwrap().len(), 3);
+        assert!(DigitVocabulary::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_word_matching() {
+        let vocabulary = DigitVocabulary::portuguese();
+
+        assert_eq!(vocabulary.word_at("trêsx"), Some(3));
+        assert_eq!(vocabulary.word_at("tres"), None);
+        assert_eq!(vocabulary.word_ending("xtrês"), Some(3));
+        assert_eq!(vocabulary.word_ending("três"), Some(3));
+        assert_eq!(vocabulary.word_ending("trê"), None);
+        assert_eq!(DigitVocabulary::spanish().word_at("tres"), Some(3));
+    }
+}
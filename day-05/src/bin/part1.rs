@@ -1,14 +1,13 @@
-use std::str::FromStr;
+use std::{error::Error, str::FromStr};
 
-use day_05::{Almanac, Category, ParseError};
+use day_05::{Almanac, Category};
 
-fn main() -> Result<(), ParseError> {
+fn main() -> Result<(), Box<dyn Error>> {
     let input = include_str!("../data/input.txt");
     let almanac = Almanac::from_str(input)?;
     let result = almanac
-        .instructions()
-        .iter()
-        .map(|m| *m.get(&Category::Location).unwrap())
+        .traces(&Category::from("location"))?
+        .map(|t| t.value())
         .min()
         .unwrap();
 
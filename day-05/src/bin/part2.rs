@@ -0,0 +1,23 @@
+use std::{error::Error, str::FromStr};
+
+use day_05::{Almanac, Category};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("../data/input.txt");
+    let almanac = Almanac::from_str(input)?;
+    let seed_ranges = almanac.seed_ranges()?;
+    let result = almanac
+        .translate_ranges(
+            almanac.seed_category(),
+            &Category::from("location"),
+            &seed_ranges,
+        )?
+        .iter()
+        .map(|r| r.start)
+        .min()
+        .unwrap();
+
+    println!("Result: {}", result);
+
+    Ok(())
+}
@@ -1,99 +1,330 @@
-use std::{collections::HashMap, error::Error, fmt::Display, str::FromStr};
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+    ops,
+    str::FromStr,
+    sync::Arc,
+    thread,
+};
 
-#[derive(Debug)]
-pub struct ParseError(String);
+mod plot;
+mod tokenizer;
+
+use tokenizer::{Line, Token};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    position: Option<(usize, usize)>,
+}
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "error parsing data from string: {}", self.0)
+        write!(f, "error parsing data from string: {}", self.message)?;
+
+        if let Some((line, column)) = self.position {
+            write!(f, " at line {}, column {}", line, column)?;
+        }
+
+        Ok(())
     }
 }
 
 impl Error for ParseError {}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Category {
-    Seed,
-    Soil,
-    Fertilizer,
-    Water,
-    Light,
-    Temperature,
-    Humidity,
-    Location,
+impl ParseError {
+    fn new(message: String) -> Self {
+        Self {
+            message,
+            position: None,
+        }
+    }
+
+    fn at(line: usize, column: usize, message: String) -> Self {
+        Self {
+            message,
+            position: Some((line, column)),
+        }
+    }
+
+    fn at_token(token: &Token<'_>, message: String) -> Self {
+        Self::at(token.line, token.column, message)
+    }
+
+    fn or_at(self, line: usize, column: usize) -> Self {
+        Self {
+            position: self.position.or(Some((line, column))),
+            ..self
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.position.map(|(line, _)| line)
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.position.map(|(_, column)| column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslateError {
+    UnknownCategory(Category),
+    Unreachable { from: Category, to: Category },
+}
+
+impl Display for TranslateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCategory(category) => write!(
+                f,
+                "error translating data: unknown category `{}` in Almanac",
+                category
+            ),
+            Self::Unreachable { from, to } => write!(
+                f,
+                "error translating data: `{}` cannot be reached from `{}`",
+                to, from
+            ),
+        }
+    }
+}
+
+impl Error for TranslateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    OverlappingRanges { other_line: usize },
+    ZeroLengthRange,
+    EmptyMap,
+    DuplicateMap { original_line: usize },
+    MissingStage(Category),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub header: String,
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {} in `{}`: ", self.line, self.header)?;
+
+        match &self.kind {
+            IssueKind::OverlappingRanges { other_line } => {
+                write!(f, "range overlaps the range at line {}", other_line)
+            }
+            IssueKind::ZeroLengthRange => write!(f, "range has zero length"),
+            IssueKind::EmptyMap => write!(f, "map has no ranges"),
+            IssueKind::DuplicateMap { original_line } => {
+                write!(f, "map duplicates the map at line {}", original_line)
+            }
+            IssueKind::MissingStage(category) => {
+                write!(f, "missing map from `{}`", category)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Category(Arc<str>);
+
 impl FromStr for Category {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "seed" => Ok(Self::Seed),
-            "soil" => Ok(Self::Soil),
-            "fertilizer" => Ok(Self::Fertilizer),
-            "water" => Ok(Self::Water),
-            "light" => Ok(Self::Light),
-            "temperature" => Ok(Self::Temperature),
-            "humidity" => Ok(Self::Humidity),
-            "location" => Ok(Self::Location),
-            _ => Err(ParseError(format!("invalid category `{}`", s))),
+        if s.is_empty() || !s.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(ParseError::new(format!("invalid category `{}`", s)));
         }
+
+        Ok(Self::from(s))
+    }
+}
+
+impl From<&str> for Category {
+    fn from(name: &str) -> Self {
+        Self(Arc::from(name))
+    }
+}
+
+impl Borrow<str> for Category {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 impl Category {
-    fn next(&self) -> Option<Self> {
-        match self {
-            Self::Seed => Some(Self::Soil),
-            Self::Soil => Some(Self::Fertilizer),
-            Self::Fertilizer => Some(Self::Water),
-            Self::Water => Some(Self::Light),
-            Self::Light => Some(Self::Temperature),
-            Self::Temperature => Some(Self::Humidity),
-            Self::Humidity => Some(Self::Location),
-            Self::Location => None,
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CategoryRegistry {
+    categories: Vec<Category>,
+    names: HashSet<Category>,
+}
+
+impl CategoryRegistry {
+    pub fn intern(&mut self, name: &str) -> Result<Category, ParseError> {
+        if let Some(category) = self.names.get(name) {
+            return Ok(category.clone());
         }
+
+        let category = Category::from_str(name)?;
+        self.names.insert(category.clone());
+        self.categories.push(category.clone());
+
+        Ok(category)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Category> {
+        self.names.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Category> {
+        self.categories.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.categories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
     }
 }
 
-#[derive(Debug)]
-struct Range {
-    destination_start: u32,
-    source_start: u32,
-    length: u32,
+#[derive(Debug, Clone)]
+pub struct Range {
+    destination_start: u64,
+    source_start: u64,
+    length: u64,
+    line: usize,
 }
 
 impl FromStr for Range {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let range_ints = s
-            .split(" ")
-            .filter(|s| !s.is_empty())
-            .map(|s| {
-                s.parse::<u32>()
-                    .map_err(|_| ParseError(format!("invalid int `{}` in CategoryMap ranges", s)))
+        let mut lines = tokenizer::lines(s, 1).filter(|l| !l.tokens.is_empty());
+        let line = lines
+            .next()
+            .ok_or(ParseError::new("missing range in CategoryMap".to_string()))?;
+
+        if let Some(extra) = lines.next() {
+            return Err(ParseError::at_token(
+                &extra.tokens[0],
+                format!(
+                    "unexpected `{}` after range in CategoryMap",
+                    extra.tokens[0].text
+                ),
+            ));
+        }
+
+        Self::parse(&line)
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.destination_start, self.source_start, self.length
+        )
+    }
+}
+
+impl PartialEq for Range {
+    fn eq(&self, other: &Self) -> bool {
+        self.destination_start == other.destination_start
+            && self.source_start == other.source_start
+            && self.length == other.length
+    }
+}
+
+impl Eq for Range {}
+
+impl Range {
+    fn parse(line: &Line<'_>) -> Result<Self, ParseError> {
+        let range_ints = line
+            .tokens
+            .iter()
+            .map(|t| {
+                t.text.parse::<u64>().map_err(|_| {
+                    ParseError::at_token(
+                        t,
+                        format!("invalid int `{}` in CategoryMap ranges", t.text),
+                    )
+                })
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        let text = line
+            .tokens
+            .iter()
+            .map(|t| t.text)
+            .collect::<Vec<_>>()
+            .join(" ");
+
         let (destination_start, source_start, length) = match &range_ints[..] {
             [dest_start, source_start, length] => Ok((*dest_start, *source_start, *length)),
-            _ => Err(ParseError(format!("invalid range `{}` in CategoryMap", s))),
+            _ => Err(ParseError::at_token(
+                &line.tokens[0],
+                format!("invalid range `{}` in CategoryMap", text),
+            )),
         }?;
 
+        if source_start.checked_add(length).is_none()
+            || destination_start.checked_add(length).is_none()
+        {
+            return Err(ParseError::at_token(
+                &line.tokens[0],
+                format!("range `{}` out of bounds in CategoryMap", text),
+            ));
+        }
+
         Ok(Self {
             destination_start,
             source_start,
             length,
+            line: line.number,
         })
     }
-}
 
-impl Range {
-    fn get_destination(&self, source: u32) -> Option<u32> {
-        let source_end = self.source_start + self.length;
+    pub fn destination_start(&self) -> u64 {
+        self.destination_start
+    }
 
-        if source >= self.source_start && source < source_end {
+    pub fn source_start(&self) -> u64 {
+        self.source_start
+    }
+
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    fn source_end(&self) -> u64 {
+        self.source_start + self.length
+    }
+
+    fn get_destination(&self, source: u64) -> Option<u64> {
+        if source >= self.source_start && source < self.source_end() {
             let offset = source - self.source_start;
             Some(self.destination_start + offset)
         } else {
@@ -102,203 +333,1583 @@ impl Range {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CategoryMap {
     source: Category,
+    destination: Category,
     ranges: Vec<Range>,
+    pieces: Vec<(ops::Range<u64>, usize)>,
+    line: usize,
 }
 
 impl FromStr for CategoryMap {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let header_line = lines.next().ok_or(ParseError(
-            "missing source and destination in CategoryMap".to_string(),
-        ))?;
+        let blocks = tokenizer::blocks(s);
+
+        match &blocks[..] {
+            [block] => Self::parse(block, &mut CategoryRegistry::default()),
+            [] => Err(ParseError::new(
+                "missing source and destination in CategoryMap".to_string(),
+            )),
+            [_, block, ..] => Err(ParseError::at_token(
+                &block[0].tokens[0],
+                format!("unexpected `{}` after CategoryMap", block[0].tokens[0].text),
+            )),
+        }
+    }
+}
+
+impl Display for CategoryMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.header())?;
 
-        let header_tokens = header_line.split(" ").collect::<Vec<_>>();
+        for range in &self.ranges {
+            write!(f, "\n{}", range)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialEq for CategoryMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.destination == other.destination
+            && self.ranges == other.ranges
+    }
+}
+
+impl Eq for CategoryMap {}
 
-        let src_and_dest = match &header_tokens[..] {
-            [src_and_dest, "map:"] => Ok(src_and_dest),
-            _ => Err(ParseError(format!(
-                "invalid header `{}` in CategoryMap",
-                header_line
-            ))),
+impl CategoryMap {
+    fn parse(block: &[Line<'_>], categories: &mut CategoryRegistry) -> Result<Self, ParseError> {
+        let header = &block[0];
+        let header_text = header
+            .tokens
+            .iter()
+            .map(|t| t.text)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let invalid_header = || {
+            ParseError::at_token(
+                &header.tokens[0],
+                format!("invalid header `{}` in CategoryMap", header_text),
+            )
+        };
+
+        let src_and_dest = match &header.tokens[..] {
+            [src_and_dest, map] if map.text == "map:" => Ok(src_and_dest),
+            _ => Err(invalid_header()),
         }?;
 
-        let source = match &src_and_dest.split("-to-").collect::<Vec<_>>()[..] {
+        let (source, destination) = match &src_and_dest.text.split("-to-").collect::<Vec<_>>()[..] {
             [src, dest] => {
-                let source = Category::from_str(src)?;
-                let destination = Category::from_str(dest)?;
+                let source = categories
+                    .intern(src)
+                    .map_err(|e| e.or_at(header.number, src_and_dest.column))?;
 
-                match source.next() {
-                    Some(n) if n == destination => Ok(()),
-                    _ => Err(ParseError(format!("forbidden map `{}`", src_and_dest))),
-                }?;
+                let destination = categories
+                    .intern(dest)
+                    .map_err(|e| e.or_at(header.number, src_and_dest.column_of(src.len() + 4)))?;
 
-                Ok(source)
+                Ok((source, destination))
             }
-            _ => Err(ParseError(format!(
-                "invalid header `{}` in CategoryMap",
-                header_line
-            ))),
+            _ => Err(invalid_header()),
         }?;
 
-        let ranges = lines
-            .map(|s| Range::from_str(s))
+        let ranges = block[1..]
+            .iter()
+            .map(Range::parse)
             .collect::<Result<Vec<_>, _>>()?;
 
-        if ranges.is_empty() {
-            return Err(ParseError(format!(
-                "missing ranges in `{}` map in Almanac",
-                src_and_dest
-            )));
+        Ok(Self::new(source, destination, ranges, header.number))
+    }
+
+    fn new(source: Category, destination: Category, ranges: Vec<Range>, line: usize) -> Self {
+        let mut pieces: Vec<(ops::Range<u64>, usize)> = vec![];
+
+        for (i, range) in ranges.iter().enumerate() {
+            let first = pieces.partition_point(|(piece, _)| piece.end <= range.source_start);
+            let mut cursor = range.source_start;
+            let mut gaps = vec![];
+
+            for (piece, _) in &pieces[first..] {
+                if piece.start >= range.source_end() {
+                    break;
+                }
+
+                if cursor < piece.start {
+                    gaps.push(cursor..piece.start);
+                }
+
+                cursor = cursor.max(piece.end);
+            }
+
+            if cursor < range.source_end() {
+                gaps.push(cursor..range.source_end());
+            }
+
+            pieces.extend(gaps.into_iter().map(|gap| (gap, i)));
+            pieces.sort_by_key(|(piece, _)| piece.start);
         }
 
-        Ok(CategoryMap { source, ranges })
+        Self {
+            source,
+            destination,
+            ranges,
+            pieces,
+            line,
+        }
     }
-}
 
-impl CategoryMap {
-    pub fn get_destination(&self, source: u32) -> u32 {
-        self.ranges
-            .iter()
-            .find_map(|r| r.get_destination(source))
+    pub fn header(&self) -> String {
+        format!("{}-to-{} map:", self.source, self.destination)
+    }
+
+    pub fn source(&self) -> &Category {
+        &self.source
+    }
+
+    pub fn destination(&self) -> &Category {
+        &self.destination
+    }
+
+    pub fn ranges(&self) -> &[Range] {
+        &self.ranges
+    }
+
+    pub fn find_range(&self, source: u64) -> Option<&Range> {
+        let i = self
+            .pieces
+            .partition_point(|(piece, _)| piece.start <= source);
+        let (piece, range) = &self.pieces[i.checked_sub(1)?];
+
+        piece.contains(&source).then(|| &self.ranges[*range])
+    }
+
+    pub fn get_destination(&self, source: u64) -> u64 {
+        self.find_range(source)
+            .and_then(|r| r.get_destination(source))
             .unwrap_or(source)
     }
-}
 
-#[derive(Debug)]
-pub struct Almanac {
-    seeds: Vec<u32>,
-    category_maps: HashMap<Category, CategoryMap>,
-}
+    fn split(&self, source: ops::Range<u64>) -> Vec<(ops::Range<u64>, u64)> {
+        let first = self
+            .pieces
+            .partition_point(|(piece, _)| piece.end <= source.start);
 
-impl FromStr for Almanac {
-    type Err = ParseError;
+        let mut pieces = vec![];
+        let mut cursor = source.start;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut frags = s.split("\n\n");
-        let seeds_str = frags.next().ok_or(ParseError(
-            "could not parse Almanac from empty string".to_string(),
-        ))?;
+        for (piece, range) in &self.pieces[first..] {
+            if cursor >= source.end {
+                break;
+            }
 
-        let seeds = match &seeds_str.split(": ").collect::<Vec<_>>()[..] {
-            ["seeds", seeds_str] => {
-                let seeds = seeds_str
-                    .split(" ")
-                    .filter(|s| !s.is_empty())
-                    .map(|s| {
-                        s.parse::<u32>().map_err(|_| {
-                            ParseError(format!("invalid int `{}` in Almanac seeds", s))
-                        })
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
+            if cursor < piece.start {
+                let end = piece.start.min(source.end);
+                pieces.push((cursor..end, cursor));
+                cursor = end;
+            }
 
-                Ok(seeds)
+            let end = piece.end.min(source.end);
+
+            if cursor < end {
+                let range = &self.ranges[*range];
+                let destination_start = range.destination_start + (cursor - range.source_start);
+                pieces.push((cursor..end, destination_start));
+                cursor = end;
             }
-            _ => Err(ParseError(format!(
-                "invalid header `{}` in Almanac",
-                seeds_str
-            ))),
-        }?;
+        }
 
-        if seeds.is_empty() {
-            return Err(ParseError("missing seeds in Almanac".to_string()));
+        if cursor < source.end {
+            pieces.push((cursor..source.end, cursor));
+        }
+
+        pieces
+    }
+
+    fn from_pieces(
+        source: Category,
+        destination: Category,
+        pieces: impl IntoIterator<Item = (ops::Range<u64>, u64)>,
+    ) -> CategoryMap {
+        let mut ranges: Vec<Range> = vec![];
+
+        for (piece, destination_start) in pieces {
+            if piece.start == destination_start || piece.is_empty() {
+                continue;
+            }
+
+            match ranges.last_mut() {
+                Some(last)
+                    if last.source_end() == piece.start
+                        && last.destination_start + last.length == destination_start =>
+                {
+                    last.length += piece.end - piece.start;
+                }
+                _ => ranges.push(Range {
+                    destination_start,
+                    source_start: piece.start,
+                    length: piece.end - piece.start,
+                    line: 0,
+                }),
+            }
         }
 
-        let category_maps = frags
-            .map(|s| {
-                let category_map = CategoryMap::from_str(s)?;
-                let source = category_map.source;
+        Self::new(source, destination, ranges, 0)
+    }
+
+    pub fn normalize(&self) -> CategoryMap {
+        Self::from_pieces(
+            self.source.clone(),
+            self.destination.clone(),
+            self.split(0..u64::MAX),
+        )
+    }
 
-                Ok((source, category_map))
+    pub fn with_identity_gaps(&self) -> CategoryMap {
+        let ranges = self
+            .split(0..u64::MAX)
+            .into_iter()
+            .map(|(source, destination_start)| Range {
+                destination_start,
+                source_start: source.start,
+                length: source.end - source.start,
+                line: 0,
             })
-            .collect::<Result<HashMap<_, _>, _>>()?;
+            .collect();
 
-        Ok(Almanac {
-            seeds,
-            category_maps,
-        })
+        Self::new(self.source.clone(), self.destination.clone(), ranges, 0)
     }
-}
 
-impl Almanac {
-    pub fn instructions(&self) -> Vec<HashMap<Category, u32>> {
-        self.seeds
+    pub fn get_destination_ranges(&self, sources: &[ops::Range<u64>]) -> Vec<ops::Range<u64>> {
+        sources
             .iter()
-            .map(|seed| {
-                let mut instructions = HashMap::new();
-                let mut cur_cat = Category::Seed;
-                let mut cur_number = *seed;
+            .filter(|source| !source.is_empty())
+            .flat_map(|source| self.split(source.clone()))
+            .map(|(source, destination_start)| {
+                destination_start..destination_start + (source.end - source.start)
+            })
+            .collect()
+    }
+
+    pub fn get_sources(&self, destination: u64) -> Vec<u64> {
+        let mut sources = self
+            .split(0..u64::MAX)
+            .into_iter()
+            .filter_map(|(source, destination_start)| {
+                let length = source.end - source.start;
 
-                loop {
-                    instructions.insert(cur_cat, cur_number);
+                if destination >= destination_start && destination - destination_start < length {
+                    Some(source.start + (destination - destination_start))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        sources.sort();
+        sources
+    }
 
-                    let cat_map = if let Some(cat_map) = self.category_maps.get(&cur_cat) {
-                        cat_map
+    pub fn get_source_ranges(&self, destinations: &[ops::Range<u64>]) -> Vec<ops::Range<u64>> {
+        let mut sources = self
+            .split(0..u64::MAX)
+            .into_iter()
+            .flat_map(|(source, destination_start)| {
+                let destination_end = destination_start + (source.end - source.start);
+
+                destinations.iter().filter_map(move |destination| {
+                    let start = destination.start.max(destination_start);
+                    let end = destination.end.min(destination_end);
+
+                    if start < end {
+                        let source_start = source.start + (start - destination_start);
+                        Some(source_start..source_start + (end - start))
                     } else {
-                        break;
-                    };
+                        None
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
 
-                    cur_number = cat_map.get_destination(cur_number);
-                    cur_cat = cur_cat.next().unwrap();
-                }
+        sources.sort_by_key(|r| r.start);
+        sources
+    }
+
+    pub fn inverse(&self) -> Option<CategoryMap> {
+        let mut pieces = self.split(0..u64::MAX);
+        pieces.sort_by_key(|(_, destination_start)| *destination_start);
+
+        let mut destination_end = 0;
+
+        for (source, destination_start) in &pieces {
+            if *destination_start < destination_end {
+                return None;
+            }
+
+            destination_end = destination_start + (source.end - source.start);
+        }
 
-                instructions
+        let ranges = pieces
+            .into_iter()
+            .filter(|(source, destination_start)| source.start != *destination_start)
+            .map(|(source, destination_start)| Range {
+                destination_start: source.start,
+                source_start: destination_start,
+                length: source.end - source.start,
+                line: 0,
             })
-            .collect::<Vec<_>>()
+            .collect();
+
+        Some(Self::new(
+            self.destination.clone(),
+            self.source.clone(),
+            ranges,
+            0,
+        ))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn compose(&self, next: &CategoryMap) -> CategoryMap {
+        assert_eq!(
+            self.destination, next.source,
+            "cannot compose maps with mismatching categories"
+        );
 
-    #[test]
-    fn test_locations() {
-        let input = "seeds: 79 14 55 13
+        let pieces = self
+            .split(0..u64::MAX)
+            .into_iter()
+            .flat_map(|(source, destination_start)| {
+                let length = source.end - source.start;
 
-seed-to-soil map:
-50 98 2
-52 50 48
+                next.split(destination_start..destination_start + length)
+                    .into_iter()
+                    .map(move |(middle, next_destination_start)| {
+                        let start = source.start + (middle.start - destination_start);
+                        let end = start + (middle.end - middle.start);
 
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
+                        (start..end, next_destination_start)
+                    })
+            });
 
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
+        Self::from_pieces(self.source.clone(), next.destination.clone(), pieces)
+    }
+}
 
-water-to-light map:
-88 18 7
-18 25 70
+#[derive(Debug, Clone, Copy)]
+pub struct Step<'a> {
+    pub map: &'a CategoryMap,
+    pub source: u64,
+    pub destination: u64,
+    pub range: Option<&'a Range>,
+}
 
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+#[derive(Debug, Clone)]
+pub struct SeedTrace<'a> {
+    seed: u64,
+    steps: Vec<Step<'a>>,
+}
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+impl Display for SeedTrace<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", SEED_CATEGORY, self.seed)?;
 
-humidity-to-location map:
-60 56 37
-56 93 4";
+        for step in &self.steps {
+            write!(f, " -> {} {}", step.map.destination, step.destination)?;
 
-        let almanac = Almanac::from_str(input).unwrap();
-        let locations: Vec<u32> = almanac
-            .instructions()
-            .iter()
-            .map(|m| *m.get(&Category::Location).unwrap())
-            .collect();
+            if let Some(range) = step.range {
+                write!(f, " via `{}`", range)?;
+            }
+        }
 
-        assert_eq!(locations, vec![82, 43, 86, 35]);
+        Ok(())
+    }
+}
+
+impl SeedTrace<'_> {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn steps(&self) -> &[Step<'_>] {
+        &self.steps
+    }
+
+    pub fn value(&self) -> u64 {
+        self.steps.last().map_or(self.seed, |s| s.destination)
+    }
+
+    pub fn value_at(&self, category: &Category) -> Option<u64> {
+        if category.name() == SEED_CATEGORY {
+            return Some(self.seed);
+        }
+
+        self.steps
+            .iter()
+            .find(|s| s.map.destination == *category)
+            .map(|s| s.destination)
+    }
+}
+
+const SEED_CATEGORY: &str = "seed";
+const LOCATION_CATEGORY: &str = "location";
+
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    line: usize,
+    categories: CategoryRegistry,
+    category_maps: Vec<CategoryMap>,
+    edges: HashMap<Category, Vec<usize>>,
+}
+
+impl FromStr for Almanac {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let blocks = tokenizer::blocks(s);
+        let mut blocks = blocks.iter();
+        let seeds_block = blocks.next().ok_or(ParseError::new(
+            "could not parse Almanac from empty string".to_string(),
+        ))?;
+
+        let seeds_header = &seeds_block[0].tokens[0];
+
+        if seeds_header.text != "seeds:" {
+            return Err(ParseError::at_token(
+                seeds_header,
+                format!("invalid header `{}` in Almanac", seeds_header.text),
+            ));
+        }
+
+        let seeds = seeds_block
+            .iter()
+            .flat_map(|l| &l.tokens)
+            .skip(1)
+            .map(|t| {
+                t.text.parse::<u64>().map_err(|_| {
+                    ParseError::at_token(t, format!("invalid int `{}` in Almanac seeds", t.text))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if seeds.is_empty() {
+            return Err(ParseError::at_token(
+                seeds_header,
+                "missing seeds in Almanac".to_string(),
+            ));
+        }
+
+        let mut categories = CategoryRegistry::default();
+        categories.intern(SEED_CATEGORY)?;
+
+        let category_maps = blocks
+            .map(|block| CategoryMap::parse(block, &mut categories))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut edges: HashMap<Category, Vec<usize>> = HashMap::new();
+
+        for (i, category_map) in category_maps.iter().enumerate() {
+            let maps = edges.entry(category_map.source.clone()).or_default();
+
+            if maps
+                .iter()
+                .all(|&j| category_maps[j].destination != category_map.destination)
+            {
+                maps.push(i);
+            }
+        }
+
+        let almanac = Almanac {
+            seeds,
+            line: seeds_block[0].number,
+            categories,
+            category_maps,
+            edges,
+        };
+
+        almanac.check_graph()?;
+
+        Ok(almanac)
+    }
+}
+
+impl Display for Almanac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seeds = self.seeds.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        write!(f, "seeds: {}", seeds.join(" "))?;
+
+        for cat_map in &self.category_maps {
+            write!(f, "\n\n{}", cat_map)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialEq for Almanac {
+    fn eq(&self, other: &Self) -> bool {
+        self.seeds == other.seeds && self.category_maps == other.category_maps
+    }
+}
+
+impl Eq for Almanac {}
+
+impl Almanac {
+    fn check_graph(&self) -> Result<(), ParseError> {
+        for start in self.categories.iter() {
+            let mut reached = HashSet::new();
+            let mut stack = vec![vec![start]];
+
+            while let Some(path) = stack.pop() {
+                for cat_map in self.maps_from(path[path.len() - 1]) {
+                    let destination = &cat_map.destination;
+
+                    if path.contains(&destination) {
+                        let cycle = path
+                            .iter()
+                            .skip_while(|&&c| c != destination)
+                            .chain([&destination])
+                            .map(|c| c.name())
+                            .collect::<Vec<_>>();
+
+                        return Err(ParseError::at(
+                            cat_map.line,
+                            1,
+                            format!("cycle `{}` in Almanac maps", cycle.join(" -> ")),
+                        ));
+                    }
+
+                    if !reached.insert(destination) {
+                        return Err(ParseError::at(
+                            cat_map.line,
+                            1,
+                            format!(
+                                "ambiguous maps from `{}` to `{}` in Almanac",
+                                start, destination
+                            ),
+                        ));
+                    }
+
+                    let mut path = path.clone();
+                    path.push(destination);
+                    stack.push(path);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = vec![];
+
+        if self.maps_from(self.seed_category()).next().is_none() {
+            issues.push(Issue {
+                header: "seeds:".to_string(),
+                line: self.line,
+                kind: IssueKind::MissingStage(self.seed_category().clone()),
+            });
+        }
+
+        for (i, cat_map) in self.category_maps.iter().enumerate() {
+            let header = cat_map.header();
+
+            if let Some(original) = self.category_maps[..i]
+                .iter()
+                .find(|m| m.source == cat_map.source && m.destination == cat_map.destination)
+            {
+                issues.push(Issue {
+                    header,
+                    line: cat_map.line,
+                    kind: IssueKind::DuplicateMap {
+                        original_line: original.line,
+                    },
+                });
+
+                continue;
+            }
+
+            if cat_map.ranges.is_empty() {
+                issues.push(Issue {
+                    header: header.clone(),
+                    line: cat_map.line,
+                    kind: IssueKind::EmptyMap,
+                });
+            }
+
+            for (j, range) in cat_map.ranges.iter().enumerate() {
+                if range.length == 0 {
+                    issues.push(Issue {
+                        header: header.clone(),
+                        line: range.line,
+                        kind: IssueKind::ZeroLengthRange,
+                    });
+                }
+
+                for other in &cat_map.ranges[..j] {
+                    if range.source_start < other.source_end()
+                        && other.source_start < range.source_end()
+                    {
+                        issues.push(Issue {
+                            header: header.clone(),
+                            line: range.line,
+                            kind: IssueKind::OverlappingRanges {
+                                other_line: other.line,
+                            },
+                        });
+                    }
+                }
+            }
+
+            if cat_map.destination.name() != LOCATION_CATEGORY
+                && self.maps_from(&cat_map.destination).next().is_none()
+            {
+                issues.push(Issue {
+                    header,
+                    line: cat_map.line,
+                    kind: IssueKind::MissingStage(cat_map.destination.clone()),
+                });
+            }
+        }
+
+        issues
+    }
+
+    pub fn normalize(&self) -> Almanac {
+        Almanac {
+            seeds: self.seeds.clone(),
+            line: self.line,
+            categories: self.categories.clone(),
+            category_maps: self.category_maps.iter().map(|m| m.normalize()).collect(),
+            edges: self.edges.clone(),
+        }
+    }
+
+    pub fn categories(&self) -> &CategoryRegistry {
+        &self.categories
+    }
+
+    pub fn category(&self, name: &str) -> Option<&Category> {
+        self.categories.get(name)
+    }
+
+    pub fn seed_category(&self) -> &Category {
+        self.categories.get(SEED_CATEGORY).unwrap()
+    }
+
+    pub fn category_maps(&self) -> &[CategoryMap] {
+        &self.category_maps
+    }
+
+    pub fn maps_from<'a>(&'a self, source: &Category) -> impl Iterator<Item = &'a CategoryMap> {
+        self.edges
+            .get(source)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.category_maps[i])
+    }
+
+    pub fn path(
+        &self,
+        from: &Category,
+        to: &Category,
+    ) -> Result<Vec<&CategoryMap>, TranslateError> {
+        for category in [from, to] {
+            if self.categories.get(category.name()).is_none() {
+                return Err(TranslateError::UnknownCategory(category.clone()));
+            }
+        }
+
+        let mut stack = vec![(from, vec![])];
+
+        while let Some((category, path)) = stack.pop() {
+            if category == to {
+                return Ok(path);
+            }
+
+            for cat_map in self.maps_from(category) {
+                let mut path = path.clone();
+                path.push(cat_map);
+                stack.push((&cat_map.destination, path));
+            }
+        }
+
+        Err(TranslateError::Unreachable {
+            from: from.clone(),
+            to: to.clone(),
+        })
+    }
+
+    pub fn traces<'a>(
+        &'a self,
+        to: &Category,
+    ) -> Result<impl Iterator<Item = SeedTrace<'a>>, TranslateError> {
+        let path = self.path(self.seed_category(), to)?;
+
+        Ok(self.seeds.iter().map(move |&seed| {
+            let mut value = seed;
+            let steps = path
+                .iter()
+                .map(|&map| {
+                    let source = value;
+                    let range = map.find_range(source);
+                    value = map.get_destination(source);
+
+                    Step {
+                        map,
+                        source,
+                        destination: value,
+                        range,
+                    }
+                })
+                .collect();
+
+            SeedTrace { seed, steps }
+        }))
+    }
+
+    pub fn composed(&self, from: &Category, to: &Category) -> Result<CategoryMap, TranslateError> {
+        let identity = CategoryMap::new(from.clone(), from.clone(), vec![], 0);
+
+        let composed = self
+            .path(from, to)?
+            .into_iter()
+            .fold(identity, |composed, cat_map| composed.compose(cat_map));
+
+        Ok(composed)
+    }
+
+    pub fn translate(
+        &self,
+        from: &Category,
+        to: &Category,
+        value: u64,
+    ) -> Result<u64, TranslateError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(value, |value, cat_map| cat_map.get_destination(value)))
+    }
+
+    pub fn translate_ranges(
+        &self,
+        from: &Category,
+        to: &Category,
+        ranges: &[ops::Range<u64>],
+    ) -> Result<Vec<ops::Range<u64>>, TranslateError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(ranges.to_vec(), |ranges, cat_map| {
+                cat_map.get_destination_ranges(&ranges)
+            }))
+    }
+
+    pub fn reverse_translate(
+        &self,
+        from: &Category,
+        value: u64,
+        to: &Category,
+    ) -> Result<Vec<u64>, TranslateError> {
+        let maps = self.path(to, from)?;
+        let mut values = vec![value];
+
+        for cat_map in maps.iter().rev() {
+            values = values
+                .iter()
+                .flat_map(|&value| cat_map.get_sources(value))
+                .collect();
+        }
+
+        values.sort();
+        Ok(values)
+    }
+
+    pub fn reverse_translate_ranges(
+        &self,
+        from: &Category,
+        ranges: &[ops::Range<u64>],
+        to: &Category,
+    ) -> Result<Vec<ops::Range<u64>>, TranslateError> {
+        let maps = self.path(to, from)?;
+        let mut ranges = ranges.to_vec();
+
+        for cat_map in maps.iter().rev() {
+            ranges = cat_map.get_source_ranges(&ranges);
+        }
+
+        Ok(ranges)
+    }
+
+    pub fn seed_ranges(&self) -> Result<Vec<ops::Range<u64>>, ParseError> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(ParseError::new(
+                "odd number of seeds cannot be read as ranges in Almanac".to_string(),
+            ));
+        }
+
+        self.seeds
+            .chunks(2)
+            .map(|pair| {
+                let (start, length) = (pair[0], pair[1]);
+                let end = start.checked_add(length).ok_or(ParseError::new(format!(
+                    "seed range `{} {}` out of bounds in Almanac",
+                    start, length
+                )))?;
+
+                Ok(start..end)
+            })
+            .collect()
+    }
+
+    pub fn min_location_parallel(&self, workers: usize) -> Result<Option<u64>, TranslateError> {
+        let path = self.path(self.seed_category(), &LOCATION_CATEGORY.into())?;
+        let path = &path;
+
+        Ok(thread::scope(|s| {
+            let chunks = self.seeds.chunks(self.seeds.len() / workers.max(1) + 1);
+            let mut handles = vec![];
+
+            for chunk in chunks {
+                handles.push(s.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&seed| {
+                            path.iter()
+                                .fold(seed, |value, cat_map| cat_map.get_destination(value))
+                        })
+                        .min()
+                }));
+            }
+
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap())
+                .min()
+        }))
+    }
+
+    pub fn min_range_location_parallel(
+        &self,
+        ranges: &[ops::Range<u64>],
+        workers: usize,
+    ) -> Result<Option<u64>, TranslateError> {
+        let path = self.path(self.seed_category(), &LOCATION_CATEGORY.into())?;
+        let path = &path;
+
+        Ok(thread::scope(|s| {
+            let mut handles = vec![];
+
+            for chunk in partition_ranges(ranges, workers.max(1)) {
+                handles.push(s.spawn(move || {
+                    chunk
+                        .into_iter()
+                        .flatten()
+                        .map(|seed| {
+                            path.iter()
+                                .fold(seed, |value, cat_map| cat_map.get_destination(value))
+                        })
+                        .min()
+                }));
+            }
+
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap())
+                .min()
+        }))
+    }
+}
+
+fn partition_ranges(ranges: &[ops::Range<u64>], parts: usize) -> Vec<Vec<ops::Range<u64>>> {
+    let total: u128 = ranges
+        .iter()
+        .map(|r| r.end.saturating_sub(r.start) as u128)
+        .sum();
+
+    let chunk_len = (total / parts as u128 + 1).min(u64::MAX as u128) as u64;
+    let mut chunks = vec![vec![]];
+    let mut remaining = chunk_len;
+
+    for range in ranges {
+        let mut start = range.start;
+
+        while start < range.end {
+            let end = range.end.min(start.saturating_add(remaining));
+            chunks.last_mut().unwrap().push(start..end);
+            remaining -= end - start;
+            start = end;
+
+            if remaining == 0 {
+                chunks.push(vec![]);
+                remaining = chunk_len;
+            }
+        }
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_locations() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let locations: Vec<u64> = almanac
+            .traces(&"location".into())
+            .unwrap()
+            .map(|t| t.value())
+            .collect();
+
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn test_location_ranges() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let seed_ranges = almanac.seed_ranges().unwrap();
+        let min_location = almanac
+            .translate_ranges(&"seed".into(), &"location".into(), &seed_ranges)
+            .unwrap()
+            .iter()
+            .map(|r| r.start)
+            .min();
+
+        assert_eq!(min_location, Some(46));
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        assert!(Range::from_str("0 18446744073709551615 1").is_err());
+        assert!(Range::from_str("18446744073709551615 0 1").is_err());
+        assert!(Range::from_str("0 18446744073709551614 1").is_ok());
+
+        let range = Range::from_str("3543571814 4000000000 500000000").unwrap();
+        assert_eq!(range.get_destination(4499999999), Some(4043571813));
+        assert_eq!(range.get_destination(4500000000), None);
+    }
+
+    #[test]
+    fn test_compose() {
+        let seed_to_soil = CategoryMap::from_str("seed-to-soil map:\n50 98 2\n52 50 48").unwrap();
+        let soil_to_fertilizer =
+            CategoryMap::from_str("soil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15").unwrap();
+        let composed = seed_to_soil.compose(&soil_to_fertilizer);
+
+        assert_eq!(composed.source().name(), "seed");
+        assert_eq!(composed.destination().name(), "fertilizer");
+
+        for seed in 0..200 {
+            assert_eq!(
+                composed.get_destination(seed),
+                soil_to_fertilizer.get_destination(seed_to_soil.get_destination(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn test_composed() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let composed = almanac
+            .composed(&"seed".into(), &"location".into())
+            .unwrap();
+
+        let locations: Vec<u64> = [79, 14, 55, 13]
+            .iter()
+            .map(|&seed| composed.get_destination(seed))
+            .collect();
+
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+        assert!(composed
+            .ranges
+            .windows(2)
+            .all(|w| w[0].source_end() <= w[1].source_start));
+
+        assert!(almanac
+            .composed(&"location".into(), &"seed".into())
+            .is_err());
+    }
+
+    #[test]
+    fn test_get_sources() {
+        let seed_to_soil = CategoryMap::from_str("seed-to-soil map:\n50 98 2\n52 50 48").unwrap();
+
+        assert_eq!(seed_to_soil.get_sources(81), vec![79]);
+        assert_eq!(seed_to_soil.get_sources(51), vec![99]);
+        assert_eq!(seed_to_soil.get_sources(10), vec![10]);
+        assert_eq!(
+            seed_to_soil.get_source_ranges(&[45..55, 150..152]),
+            vec![45..50, 50..53, 98..100, 150..152]
+        );
+
+        let collapsing = CategoryMap::from_str("seed-to-soil map:\n10 0 5").unwrap();
+        assert_eq!(collapsing.get_sources(12), vec![2, 12]);
+        assert_eq!(collapsing.get_sources(2), vec![]);
+        assert!(collapsing.inverse().is_none());
+
+        let inverse = seed_to_soil.inverse().unwrap();
+        assert_eq!(inverse.source().name(), "soil");
+        assert_eq!(inverse.destination().name(), "seed");
+
+        for seed in 0..200 {
+            assert_eq!(
+                inverse.get_destination(seed_to_soil.get_destination(seed)),
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_reverse_translate() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+
+        assert_eq!(
+            almanac.reverse_translate(&"location".into(), 35, &"seed".into()),
+            Ok(vec![13])
+        );
+
+        let seeds = almanac
+            .reverse_translate_ranges(&"location".into(), &[46..47, 82..83], &"seed".into())
+            .unwrap();
+
+        assert_eq!(seeds, vec![79..80, 82..83]);
+        assert_eq!(
+            almanac.reverse_translate(&"seed".into(), 35, &"location".into()),
+            Err(TranslateError::Unreachable {
+                from: "location".into(),
+                to: "seed".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_translate() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+
+        assert_eq!(
+            almanac.translate(&"seed".into(), &"location".into(), 79),
+            Ok(82)
+        );
+        assert_eq!(
+            almanac.translate(&"soil".into(), &"humidity".into(), 81),
+            Ok(78)
+        );
+        assert_eq!(
+            almanac.translate(&"water".into(), &"water".into(), 7),
+            Ok(7)
+        );
+        assert_eq!(
+            almanac.translate(&"humidity".into(), &"soil".into(), 7),
+            Err(TranslateError::Unreachable {
+                from: "humidity".into(),
+                to: "soil".into()
+            })
+        );
+
+        let incomplete =
+            Almanac::from_str(INPUT.split("\n\nwater-to-light").next().unwrap()).unwrap();
+
+        assert_eq!(
+            incomplete.translate(&"seed".into(), &"water".into(), 79),
+            Ok(81)
+        );
+        assert_eq!(
+            incomplete.translate(&"seed".into(), &"location".into(), 79),
+            Err(TranslateError::UnknownCategory("location".into()))
+        );
+        assert_eq!(
+            incomplete.translate_ranges(&"water".into(), &"fertilizer".into(), &[79..93, 55..68]),
+            Err(TranslateError::Unreachable {
+                from: "water".into(),
+                to: "fertilizer".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_category_graph() {
+        let input = "seeds: 1 20
+
+seed-to-soil map:
+10 0 5
+
+soil-to-moisture map:
+100 10 5
+
+seed-to-sunlight map:
+0 1 1
+
+moisture-to-location map:
+0 100 1";
+
+        let almanac = Almanac::from_str(input).unwrap();
+        let names: Vec<&str> = almanac.categories().iter().map(|c| c.name()).collect();
+
+        assert_eq!(
+            names,
+            vec!["seed", "soil", "moisture", "sunlight", "location"]
+        );
+        assert_eq!(
+            almanac
+                .path(&"seed".into(), &"location".into())
+                .unwrap()
+                .iter()
+                .map(|m| m.destination().name())
+                .collect::<Vec<_>>(),
+            vec!["soil", "moisture", "location"]
+        );
+        assert_eq!(
+            almanac.translate(&"seed".into(), &"location".into(), 0),
+            Ok(0)
+        );
+        assert_eq!(
+            almanac.translate(&"seed".into(), &"sunlight".into(), 1),
+            Ok(0)
+        );
+        assert_eq!(
+            almanac.translate(&"sunlight".into(), &"location".into(), 1),
+            Err(TranslateError::Unreachable {
+                from: "sunlight".into(),
+                to: "location".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_category_graph_invalid() {
+        let cycle = "seeds: 1
+
+seed-to-soil map:
+10 0 5
+
+soil-to-water map:
+100 10 5
+
+water-to-soil map:
+0 1 1";
+
+        let ambiguous = "seeds: 1
+
+seed-to-soil map:
+10 0 5
+
+soil-to-water map:
+100 10 5
+
+seed-to-water map:
+0 1 1";
+
+        assert!(Almanac::from_str(cycle).is_err());
+        assert!(Almanac::from_str(ambiguous).is_err());
+        assert!(Almanac::from_str("seeds: 1\n\nseed-to-seed map:\n0 1 1").is_err());
+        assert!(Almanac::from_str("seeds: 1\n\nseed-to-so il map:\n0 1 1").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        assert_eq!(almanac.validate(), vec![]);
+
+        let input = "seeds: 1 20
+
+seed-to-soil map:
+10 0 5
+12 3 4
+0 20 0
+
+soil-to-water map:
+100 10 5
+
+seed-to-soil map:
+0 1 1";
+
+        let almanac = Almanac::from_str(input).unwrap();
+        let issues = almanac.validate();
+
+        assert_eq!(
+            issues,
+            vec![
+                Issue {
+                    header: "seed-to-soil map:".to_string(),
+                    line: 5,
+                    kind: IssueKind::OverlappingRanges { other_line: 4 }
+                },
+                Issue {
+                    header: "seed-to-soil map:".to_string(),
+                    line: 6,
+                    kind: IssueKind::ZeroLengthRange
+                },
+                Issue {
+                    header: "soil-to-water map:".to_string(),
+                    line: 8,
+                    kind: IssueKind::MissingStage("water".into())
+                },
+                Issue {
+                    header: "seed-to-soil map:".to_string(),
+                    line: 11,
+                    kind: IssueKind::DuplicateMap { original_line: 3 }
+                },
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "line 5 in `seed-to-soil map:`: range overlaps the range at line 4"
+        );
+        assert_eq!(almanac.translate(&"seed".into(), &"soil".into(), 0), Ok(10));
+
+        let almanac = Almanac::from_str("seeds: 1").unwrap();
+        assert_eq!(
+            almanac.validate(),
+            vec![Issue {
+                header: "seeds:".to_string(),
+                line: 1,
+                kind: IssueKind::MissingStage("seed".into())
+            }]
+        );
+
+        let almanac = Almanac::from_str("\n\nseeds: 1").unwrap();
+        assert_eq!(almanac.validate()[0].line, 3);
+
+        let almanac = Almanac::from_str("seeds: 1\n\nseed-to-location map:").unwrap();
+        assert_eq!(
+            almanac.validate(),
+            vec![Issue {
+                header: "seed-to-location map:".to_string(),
+                line: 3,
+                kind: IssueKind::EmptyMap
+            }]
+        );
+        assert_eq!(
+            almanac.validate()[0].to_string(),
+            "line 3 in `seed-to-location map:`: map has no ranges"
+        );
+    }
+
+    #[test]
+    fn test_sorted_lookups() {
+        let cat_map =
+            CategoryMap::from_str("a-to-b map:\n60 56 37\n56 93 4\n0 20 0\n10 0 5").unwrap();
+
+        assert!(cat_map
+            .pieces
+            .windows(2)
+            .all(|w| w[0].0.end <= w[1].0.start));
+
+        for (source, destination) in [
+            (0, 10),
+            (4, 14),
+            (5, 5),
+            (56, 60),
+            (92, 96),
+            (96, 59),
+            (97, 97),
+        ] {
+            assert_eq!(cat_map.get_destination(source), destination);
+        }
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        let cat_map = CategoryMap::from_str("a-to-b map:\n100 0 10\n50 5 3").unwrap();
+
+        assert_eq!(cat_map.get_destination(8), 108);
+        assert_eq!(cat_map.get_destination(5), 105);
+        assert_eq!(cat_map.find_range(6).map(|r| r.line()), Some(2));
+        assert_eq!(cat_map.normalize().to_string(), "a-to-b map:\n100 0 10");
+
+        let cat_map = CategoryMap::from_str("a-to-b map:\n50 5 3\n100 0 10").unwrap();
+
+        assert_eq!(cat_map.get_destination(4), 104);
+        assert_eq!(cat_map.get_destination(6), 51);
+        assert_eq!(cat_map.get_destination(8), 108);
+        assert_eq!(
+            cat_map.get_destination_ranges(&[0..5, 5..10]),
+            vec![100..105, 50..53, 108..110]
+        );
+        assert_eq!(
+            CategoryMap::from_str(&cat_map.to_string())
+                .unwrap()
+                .get_destination(6),
+            51
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let cat_map =
+            CategoryMap::from_str("a-to-b map:\n15 5 5\n10 0 5\n20 10 0\n30 30 10\n40 20 5")
+                .unwrap();
+
+        let normalized = cat_map.normalize();
+        let ranges: Vec<(u64, u64, u64)> = normalized
+            .ranges
+            .iter()
+            .map(|r| (r.destination_start, r.source_start, r.length))
+            .collect();
+
+        assert_eq!(ranges, vec![(10, 0, 10), (40, 20, 5)]);
+
+        for source in 0..100 {
+            assert_eq!(
+                normalized.get_destination(source),
+                cat_map.get_destination(source)
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_identity_gaps() {
+        let cat_map = CategoryMap::from_str("a-to-b map:\n50 98 2\n52 50 48").unwrap();
+        let filled = cat_map.with_identity_gaps();
+        let ranges: Vec<(u64, u64, u64)> = filled
+            .ranges
+            .iter()
+            .map(|r| (r.destination_start, r.source_start, r.length))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 0, 50),
+                (52, 50, 48),
+                (50, 98, 2),
+                (100, 100, u64::MAX - 100)
+            ]
+        );
+        assert_eq!(filled.normalize().ranges.len(), 2);
+    }
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u64) -> u64 {
+            self.next() % bound
+        }
+    }
+
+    fn random_almanac(rng: &mut Rng) -> String {
+        let categories = ["seed", "soil", "fertilizer", "water", "light", "location"];
+        let seeds: Vec<String> = (0..1 + rng.below(6))
+            .map(|_| rng.below(1000).to_string())
+            .collect();
+
+        let mut blocks = vec![format!("seeds: {}", seeds.join(" "))];
+
+        for pair in categories.windows(2).take(rng.below(6) as usize) {
+            let mut block = format!("{}-to-{} map:", pair[0], pair[1]);
+
+            for _ in 0..rng.below(5) {
+                let bound = if rng.below(4) == 0 { u64::MAX / 2 } else { 200 };
+                let length = rng.below(bound);
+                let destination_start = rng.below(u64::MAX - length);
+                let source_start = rng.below(bound);
+
+                block.push_str(&format!(
+                    "\n{} {} {}",
+                    destination_start, source_start, length
+                ));
+            }
+
+            blocks.push(block);
+        }
+
+        blocks.join("\n\n")
+    }
+
+    #[test]
+    fn test_display() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let seed_to_soil = &almanac.category_maps()[0];
+
+        assert_eq!(
+            seed_to_soil.to_string(),
+            "seed-to-soil map:\n50 98 2\n52 50 48"
+        );
+        assert_eq!(seed_to_soil.ranges[0].to_string(), "50 98 2");
+        assert_eq!(
+            almanac.to_string().lines().next(),
+            Some("seeds: 79 14 55 13")
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        let almanacs = (0..500)
+            .map(|_| random_almanac(&mut rng))
+            .chain([INPUT.to_string()]);
+
+        for input in almanacs {
+            let almanac = Almanac::from_str(&input).unwrap();
+            let normalized = almanac.normalize();
+
+            assert_eq!(Almanac::from_str(&almanac.to_string()).unwrap(), almanac);
+            assert_eq!(
+                Almanac::from_str(&normalized.to_string()).unwrap(),
+                normalized
+            );
+        }
+    }
+
+    #[test]
+    fn test_traces() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let trace = almanac.traces(&"location".into()).unwrap().nth(1).unwrap();
+
+        assert_eq!(trace.seed(), 14);
+        assert_eq!(trace.value(), 43);
+        assert_eq!(trace.value_at(&"seed".into()), Some(14));
+        assert_eq!(trace.value_at(&"water".into()), Some(49));
+        assert_eq!(trace.value_at(&"unknown".into()), None);
+        assert!(trace.steps()[0].range.is_none());
+        assert_eq!(trace.steps()[1].range.map(|r| r.line()), Some(10));
+        assert_eq!(
+            trace.to_string(),
+            "seed 14 -> soil 14 -> fertilizer 53 via `39 0 15` -> water 49 via `49 53 8` \
+             -> light 42 via `18 25 70` -> temperature 42 -> humidity 43 via `1 0 69` \
+             -> location 43"
+        );
+    }
+
+    #[test]
+    fn test_tolerant_parsing() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let variants = [
+            INPUT.replace('\n', "\r\n"),
+            format!("{}\n\n\n", INPUT),
+            INPUT.replace("\n\n", "\n\n\n"),
+            INPUT.replace("\n\n", "\n \t\n"),
+            INPUT.replace(' ', "  \t"),
+            format!("\u{feff}{}", INPUT),
+        ];
+
+        for variant in variants {
+            assert_eq!(Almanac::from_str(&variant).unwrap(), almanac);
+        }
+
+        let windows = Almanac::from_str(&INPUT.replace('\n', "\r\n")).unwrap();
+        assert_eq!(windows.validate(), vec![]);
+        assert_eq!(windows.category_maps()[1].line, 7);
+    }
+
+    #[test]
+    fn test_parse_error_positions() {
+        let cases = [
+            ("seeds: 79 x1", (1, 11)),
+            ("seed: 79", (1, 1)),
+            ("seeds:", (1, 1)),
+            ("seeds: 79\r\n\r\nseed-to-soil map:\r\n50 98 z", (4, 7)),
+            ("seeds: 79\n\n  seed-to-so/il map:\n50 98 2", (3, 11)),
+            ("seeds: 79\n\nseed-to-soil map:\n50 98 2\n\n\n1 2", (7, 1)),
+            ("seeds: 1\n\n50 98 2\n\nseed-to-soil map:\n1 0 5", (3, 1)),
+            ("seeds: 1\r\n \r\n50 98 2", (3, 1)),
+            ("seeds: 79\nseed-to-soil map:\n50 98 2", (2, 1)),
+            ("seeds: 79\n\nseedtosoil map:\n50 98 2", (3, 1)),
+            (
+                "seeds: 79\n\nseed-to-soil map:\n50 98 2\nsoil-to-seed map:\n1 2 3",
+                (5, 1),
+            ),
+        ];
+
+        for (input, position) in cases {
+            let error = Almanac::from_str(input).unwrap_err();
+            assert_eq!(
+                (error.line(), error.column()),
+                (Some(position.0), Some(position.1))
+            );
+        }
+
+        assert_eq!(
+            Almanac::from_str("seeds: 79 x1").unwrap_err().to_string(),
+            "error parsing data from string: invalid int `x1` in Almanac seeds at line 1, column 11"
+        );
+        assert!(Almanac::from_str("\r\n\n").is_err());
+        assert_eq!(
+            CategoryMap::from_str("a-to-b map:\n1 2 3\n\n4 5 6")
+                .unwrap_err()
+                .line(),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_partition_ranges() {
+        let chunks = partition_ranges(&[79..93, 55..68, 10..10], 3);
+
+        assert_eq!(
+            chunks,
+            vec![vec![79..89], vec![89..93, 55..61], vec![61..68]]
+        );
+        assert_eq!(partition_ranges(&[], 4), vec![vec![]]);
+    }
+
+    #[test]
+    fn test_min_location_parallel() {
+        let almanac = Almanac::from_str(INPUT).unwrap();
+        let seed_ranges = almanac.seed_ranges().unwrap();
+
+        for workers in [0, 1, 3, 8] {
+            assert_eq!(almanac.min_location_parallel(workers), Ok(Some(35)));
+            assert_eq!(
+                almanac.min_range_location_parallel(&seed_ranges, workers),
+                Ok(Some(46))
+            );
+        }
+
+        assert_eq!(almanac.min_range_location_parallel(&[], 2), Ok(None));
+
+        let incomplete =
+            Almanac::from_str(INPUT.split("\n\nwater-to-light").next().unwrap()).unwrap();
+
+        assert!(incomplete.min_location_parallel(2).is_err());
     }
 }
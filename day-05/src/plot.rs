@@ -0,0 +1,165 @@
+use std::iter;
+
+use crate::{Almanac, CategoryMap};
+
+const CSV_HEADER: &str = "source_start,source_end,destination_start,destination_end,offset,kind";
+
+impl CategoryMap {
+    fn csv_rows(&self) -> Vec<String> {
+        self.split(0..u64::MAX)
+            .into_iter()
+            .map(|(source, destination_start)| {
+                let length = source.end - source.start;
+                let offset = destination_start as i128 - source.start as i128;
+                let kind = match self.find_range(source.start) {
+                    Some(_) => "range",
+                    None => "identity",
+                };
+
+                format!(
+                    "{},{},{},{},{},{}",
+                    source.start,
+                    source.end,
+                    destination_start,
+                    destination_start + length,
+                    offset,
+                    kind
+                )
+            })
+            .collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = CSV_HEADER.to_string();
+
+        for row in self.csv_rows() {
+            csv.push('\n');
+            csv.push_str(&row);
+        }
+
+        csv
+    }
+
+    pub fn plot(&self, width: usize, height: usize) -> String {
+        let (width, height) = (width.max(1), height.max(1));
+        let bound = self
+            .ranges
+            .iter()
+            .map(|r| r.source_end().max(r.destination_start + r.length))
+            .max()
+            .unwrap_or(1)
+            .max(1);
+
+        let mut grid = vec![vec![' '; width]; height];
+
+        for (x, column) in (0..width).map(|x| (x, x as u128 * bound as u128 / width as u128)) {
+            let source = column as u64;
+            let destination = self.get_destination(source);
+            let y = (destination as u128 * height as u128 / bound as u128) as usize;
+
+            grid[height - 1 - y.min(height - 1)][x] = match self.find_range(source) {
+                Some(_) => '*',
+                None => '.',
+            };
+        }
+
+        let mut plot = format!("{} destination 0..{}\n", self.header(), bound);
+
+        for row in grid {
+            plot.push('|');
+            plot.extend(row);
+            plot.push('\n');
+        }
+
+        plot.push('+');
+        plot.extend(iter::repeat_n('-', width));
+        plot.push_str(&format!("\n source 0..{}", bound));
+
+        plot
+    }
+}
+
+impl Almanac {
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("map,{}", CSV_HEADER);
+
+        for cat_map in &self.category_maps {
+            let name = format!("{}-to-{}", cat_map.source, cat_map.destination);
+
+            for row in cat_map.csv_rows() {
+                csv.push_str(&format!("\n{},{}", name, row));
+            }
+        }
+
+        csv
+    }
+
+    pub fn plot(&self, width: usize, height: usize) -> String {
+        self.category_maps
+            .iter()
+            .map(|m| m.plot(width, height))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let cat_map = CategoryMap::from_str("seed-to-soil map:\n50 98 2\n52 50 48").unwrap();
+
+        assert_eq!(
+            cat_map.to_csv(),
+            format!(
+                "{}\n0,50,0,50,0,identity\n50,98,52,100,2,range\n\
+                 98,100,50,52,-48,range\n100,{},100,{},0,identity",
+                CSV_HEADER,
+                u64::MAX,
+                u64::MAX
+            )
+        );
+
+        let almanac = Almanac::from_str("seeds: 1\n\nseed-to-soil map:\n10 0 5").unwrap();
+
+        assert_eq!(
+            almanac.to_csv().lines().take(3).collect::<Vec<_>>(),
+            vec![
+                format!("map,{}", CSV_HEADER).as_str(),
+                "seed-to-soil,0,5,10,15,10,range",
+                format!("seed-to-soil,5,{},5,{},0,identity", u64::MAX, u64::MAX).as_str(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plot() {
+        let cat_map = CategoryMap::from_str("a-to-b map:\n0 4 4").unwrap();
+
+        assert_eq!(
+            cat_map.plot(8, 4),
+            "a-to-b map: destination 0..8\n\
+             |        \n\
+             |        \n\
+             |  ..  **\n\
+             |..  **  \n\
+             +--------\n \
+             source 0..8"
+        );
+
+        let almanac =
+            Almanac::from_str("seeds: 1\n\nseed-to-soil map:\n0 4 4\n\nsoil-to-water map:\n1 0 1")
+                .unwrap();
+
+        let plots = almanac.plot(8, 4);
+        let plots = plots.split("\n\n").collect::<Vec<_>>();
+
+        assert_eq!(plots.len(), 2);
+        assert!(plots[0].starts_with("seed-to-soil map:"));
+        assert!(plots[1].starts_with("soil-to-water map:"));
+    }
+}
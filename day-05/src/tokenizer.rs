@@ -0,0 +1,69 @@
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Token<'_> {
+    pub fn column_of(&self, byte_offset: usize) -> usize {
+        self.column + self.text[..byte_offset].chars().count()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub tokens: Vec<Token<'a>>,
+}
+
+fn tokenize(line: &str, number: usize) -> Line<'_> {
+    let mut tokens = vec![];
+    let mut start = None;
+    let mut column = 0;
+
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        column += 1;
+
+        match start {
+            None if !c.is_whitespace() => start = Some((i, column)),
+            Some((start_index, start_column)) if c.is_whitespace() => {
+                tokens.push(Token {
+                    text: &line[start_index..i],
+                    line: number,
+                    column: start_column,
+                });
+
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    Line { number, tokens }
+}
+
+pub fn lines(s: &str, first_line: usize) -> impl Iterator<Item = Line<'_>> {
+    s.trim_start_matches('\u{feff}')
+        .lines()
+        .zip(first_line..)
+        .map(|(line, number)| tokenize(line, number))
+}
+
+pub fn blocks(s: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks: Vec<Vec<Line<'_>>> = vec![];
+    let mut separated = true;
+
+    for line in lines(s, 1) {
+        match blocks.last_mut() {
+            _ if line.tokens.is_empty() => separated = true,
+            Some(block) if !separated => block.push(line),
+            _ => {
+                blocks.push(vec![line]);
+                separated = false;
+            }
+        }
+    }
+
+    blocks
+}